@@ -0,0 +1,279 @@
+use std::collections::BTreeSet;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use contract_bindings::payout_factory_native_addr::PayoutFactoryNativeAddr as PayoutFactory;
+use ethers::abi::Address;
+use ethers::contract::LogMeta;
+use ethers::providers::{Http, JsonRpcClient, Middleware, Provider};
+use ethers::types::H256;
+use fevm_utils::filecoin_to_eth_address;
+use fvm_shared::address::Address as FilecoinAddress;
+use log::info;
+use serde_json::Value;
+use tabled::Tabled;
+
+use crate::error::CLIError;
+use crate::utils::{format_table, ADMIN_ROLE, EAM_ACTOR_ID, LOG_QUERY_RANGE};
+
+/// A change of the admin role of a factory, from its `RoleGranted` and
+/// `RoleRevoked` events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdminChange {
+    Granted(Address),
+    Revoked(Address),
+}
+
+/// An admin of a factory, with its Ethereum and Filecoin address forms.
+#[derive(Debug, Clone, PartialEq, Eq, Tabled)]
+pub struct FactoryAdmin {
+    #[tabled(rename = "Ethereum Address", display_with = "display_eth_address")]
+    pub eth_address: Address,
+    #[tabled(rename = "f410 Address")]
+    pub f410_address: String,
+    #[tabled(rename = "ID Address")]
+    pub id_address: String,
+    /// Whether `hasRole` confirms the account holds the role.
+    #[tabled(rename = "hasRole")]
+    pub confirmed: bool,
+}
+
+fn display_eth_address(address: &Address) -> String {
+    format!("{:?}", address)
+}
+
+/// Replays the admin role changes in chain order and returns the accounts
+/// left holding the role.
+pub fn replay_admin_changes(mut changes: Vec<(AdminChange, LogMeta)>) -> BTreeSet<Address> {
+    changes.sort_by_key(|(_, meta)| (meta.block_number, meta.log_index));
+    let mut admins = BTreeSet::new();
+    for (change, _) in changes {
+        match change {
+            AdminChange::Granted(account) => admins.insert(account),
+            AdminChange::Revoked(account) => admins.remove(&account),
+        };
+    }
+    admins
+}
+
+/// Fetches the admin role changes of a factory since `from_block`, querying
+/// the logs in ranges of `LOG_QUERY_RANGE` blocks.
+pub async fn admin_changes(
+    provider: &Provider<Http>,
+    factory_addr: &str,
+    from_block: u64,
+) -> Result<Vec<(AdminChange, LogMeta)>, CLIError> {
+    let factory = PayoutFactory::new(Address::from_str(factory_addr)?, Arc::new(provider.clone()));
+    let latest = provider.get_block_number().await?.as_u64();
+    let role = H256::from(ADMIN_ROLE);
+
+    let mut changes = Vec::new();
+    for start in (from_block..=latest).step_by(LOG_QUERY_RANGE as usize) {
+        let end = (start + LOG_QUERY_RANGE - 1).min(latest);
+        let granted = factory
+            .role_granted_filter()
+            .topic1(role)
+            .from_block(start)
+            .to_block(end)
+            .query_with_meta()
+            .await?;
+        changes.extend(
+            granted
+                .into_iter()
+                .map(|(event, meta)| (AdminChange::Granted(event.account), meta)),
+        );
+        let revoked = factory
+            .role_revoked_filter()
+            .topic1(role)
+            .from_block(start)
+            .to_block(end)
+            .query_with_meta()
+            .await?;
+        changes.extend(
+            revoked
+                .into_iter()
+                .map(|(event, meta)| (AdminChange::Revoked(event.account), meta)),
+        );
+    }
+    Ok(changes)
+}
+
+/// Works out the admins of a factory from its role events since `from_block`
+/// and checks each one with `hasRole`.
+pub async fn get_factory_admins(
+    provider: &Provider<Http>,
+    factory_addr: &str,
+    from_block: u64,
+) -> Result<Vec<FactoryAdmin>, CLIError> {
+    let factory = PayoutFactory::new(Address::from_str(factory_addr)?, Arc::new(provider.clone()));
+    let changes = admin_changes(provider, factory_addr, from_block).await?;
+
+    let mut admins = Vec::new();
+    for account in replay_admin_changes(changes) {
+        let confirmed = factory.has_role(ADMIN_ROLE, account).call().await?;
+        let f410_address = FilecoinAddress::new_delegated(EAM_ACTOR_ID, account.as_bytes())?;
+        admins.push(FactoryAdmin {
+            eth_address: account,
+            f410_address: f410_address.to_string(),
+            id_address: lookup_id(provider, &f410_address.to_string())
+                .await
+                .unwrap_or_else(|| String::from("-")),
+            confirmed,
+        });
+    }
+    Ok(admins)
+}
+
+/// ID address of an actor, or `None` if the address has no actor on chain.
+pub async fn lookup_id<P: JsonRpcClient>(provider: &Provider<P>, address: &str) -> Option<String> {
+    provider
+        .request::<(&str, Value), String>("Filecoin.StateLookupID", (address, Value::Null))
+        .await
+        .ok()
+}
+
+/// ID address of an actor, refusing addresses that are not an actor on chain.
+pub async fn resolve_id<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    address: &str,
+) -> Result<String, CLIError> {
+    lookup_id(provider, address)
+        .await
+        .ok_or_else(|| CLIError::InvalidInput(format!("{} is not an actor on chain", address)))
+}
+
+/// Resolves the Ethereum address of an account to grant the admin role to,
+/// refusing addresses that are not an actor on chain, such as mistyped ones.
+pub async fn resolve_grantee<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    address: &str,
+    rpc_url: &str,
+) -> Result<Address, CLIError> {
+    let eth_address = filecoin_to_eth_address(address, rpc_url)
+        .await
+        .map_err(|error| {
+            CLIError::InvalidInput(format!(
+                "{} does not resolve to an Ethereum address: {}",
+                address, error
+            ))
+        })?;
+    if lookup_id(provider, address).await.is_none() {
+        return Err(CLIError::InvalidInput(format!(
+            "{} is not an actor on chain",
+            address
+        )));
+    }
+    Ok(Address::from_str(&eth_address)?)
+}
+
+/// Refuses to revoke the admin role of `account` if no other account holds
+/// it, as the factory could then never run a payout again. `allow_lockout`
+/// lifts the check.
+///
+/// The other admins are read from the role events since `from_block`, so the
+/// revocation is also refused when `account` holds the role on chain but was
+/// granted it before `from_block`, as admins granted at the same time would
+/// be missed too.
+pub async fn check_admin_revocation(
+    provider: &Provider<Http>,
+    factory_addr: &str,
+    account: Address,
+    from_block: u64,
+    allow_lockout: bool,
+) -> Result<(), CLIError> {
+    let factory = PayoutFactory::new(Address::from_str(factory_addr)?, Arc::new(provider.clone()));
+    if !factory.has_role(ADMIN_ROLE, account).call().await? {
+        info!(
+            "{:?} does not hold the admin role, nothing to revoke",
+            account
+        );
+        return Ok(());
+    }
+    let admins = get_factory_admins(provider, factory_addr, from_block).await?;
+    if !admins
+        .iter()
+        .any(|admin| admin.eth_address == account && admin.confirmed)
+    {
+        return Err(CLIError::InvalidInput(format!(
+            "{:?} holds the admin role but was not granted it since block {}, pass the block the factory was deployed at as --from-block",
+            account, from_block
+        )));
+    }
+    let remaining = admins
+        .iter()
+        .filter(|admin| admin.eth_address != account && admin.confirmed)
+        .count();
+    info!(
+        "{} admins would remain after revoking {:?}",
+        remaining, account
+    );
+    if remaining == 0 && !allow_lockout {
+        return Err(CLIError::LastAdmin(account));
+    }
+    Ok(())
+}
+
+/// Prints the admins of a factory.
+pub async fn list_admins(
+    provider: &Provider<Http>,
+    factory_addr: &str,
+    from_block: u64,
+) -> Result<(), CLIError> {
+    let admins = get_factory_admins(provider, factory_addr, from_block).await?;
+    info!(
+        "{} admins of {}\n{}",
+        admins.len(),
+        factory_addr,
+        format_table(admins.iter())
+    );
+    for admin in admins.iter().filter(|admin| !admin.confirmed) {
+        info!(
+            "{:?} was granted the admin role but hasRole does not confirm it",
+            admin.eth_address
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replay_admin_changes, AdminChange};
+    use ethers::abi::Address;
+    use ethers::contract::LogMeta;
+    use ethers::types::{U256, U64};
+
+    fn meta(block: u64, index: u64) -> LogMeta {
+        LogMeta {
+            address: Address::zero(),
+            block_number: U64::from(block),
+            block_hash: Default::default(),
+            transaction_hash: Default::default(),
+            transaction_index: U64::zero(),
+            log_index: U256::from(index),
+        }
+    }
+
+    #[test]
+    fn test_replay_admin_changes() {
+        let deployer = Address::from_low_u64_be(1);
+        let admin = Address::from_low_u64_be(2);
+        // logs are queried per event type, out of chain order
+        let changes = vec![
+            (AdminChange::Granted(deployer), meta(10, 0)),
+            (AdminChange::Granted(admin), meta(20, 1)),
+            (AdminChange::Granted(deployer), meta(30, 0)),
+            (AdminChange::Revoked(deployer), meta(20, 2)),
+        ];
+        let admins = replay_admin_changes(changes);
+        assert_eq!(
+            admins.into_iter().collect::<Vec<_>>(),
+            vec![deployer, admin]
+        );
+
+        let changes = vec![
+            (AdminChange::Revoked(admin), meta(20, 2)),
+            (AdminChange::Granted(admin), meta(20, 1)),
+        ];
+        assert!(replay_admin_changes(changes).is_empty());
+    }
+}
@@ -2,19 +2,21 @@ use cli::commands::Cli;
 use log::{error, info};
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() {
     colog::init();
     banner();
 
-    match Cli::create() {
-        Ok(cli) => match cli.run().await {
-            Ok(_) => info!("success"),
-            Err(e) => error!("{}", e),
-        },
-        Err(e) => error!("{}", e),
+    let result = match Cli::create() {
+        Ok(cli) => cli.run().await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(_) => info!("success"),
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(e.exit_code());
+        }
     }
-
-    Ok(())
 }
 
 pub fn banner() {
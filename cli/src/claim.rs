@@ -0,0 +1,244 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use ethers::providers::{Http, Provider};
+use ethers::types::U256;
+use fevm_utils::filecoin_to_eth_address;
+use log::{error, info};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::error::CLIError;
+use crate::mpool::NonceAllocator;
+use crate::utils::{
+    format_u256, get_unreleased_payout_contracts, release_all_selected_payouts,
+    release_all_selected_payouts_filecoin_signing, ReleaseProgress, SignatureMethod,
+};
+
+/// Row of a claim CSV, listing an address to release funds to.
+#[derive(Debug, Deserialize)]
+struct ClaimAddress {
+    #[serde(rename = "Address")]
+    address: String,
+}
+
+/// Outcome of releasing the funds of one address, as written to the result
+/// CSV.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ClaimResult {
+    pub address: String,
+    /// FIL released to the address.
+    #[serde(rename = "FIL")]
+    pub released: Decimal,
+    /// Hashes of the transactions, or CIDs of the messages, sent for the
+    /// address, separated by spaces.
+    pub tx_hash: String,
+    /// Why releasing the funds failed, empty if it did not.
+    pub error: String,
+}
+
+impl ClaimResult {
+    fn new(
+        address: &str,
+        progress: ReleaseProgress,
+        error: Option<CLIError>,
+    ) -> Result<Self, CLIError> {
+        Ok(ClaimResult {
+            address: address.to_string(),
+            released: format_u256(progress.released)?,
+            tx_hash: progress.transactions.join(" "),
+            error: error.map_or(String::new(), |error| error.to_string()),
+        })
+    }
+}
+
+/// Reads the addresses of a claim CSV, which has a single `Address` column.
+pub fn read_claim_addresses(claim_csv: &PathBuf) -> Result<Vec<String>, CLIError> {
+    let mut reader = csv::Reader::from_path(claim_csv)?;
+    let mut addresses = Vec::new();
+    for record in reader.deserialize() {
+        let record: ClaimAddress = record?;
+        addresses.push(record.address.trim().to_string());
+    }
+    Ok(addresses)
+}
+
+/// Writes claim results to a CSV as they come in, such that the outcome of
+/// the addresses already processed is kept if the batch is interrupted.
+struct ClaimResultWriter {
+    writer: csv::Writer<std::fs::File>,
+    results: Vec<ClaimResult>,
+}
+
+impl ClaimResultWriter {
+    fn new(path: &PathBuf) -> Result<Self, CLIError> {
+        Ok(ClaimResultWriter {
+            writer: csv::Writer::from_path(path)?,
+            results: Vec::new(),
+        })
+    }
+
+    fn write(&mut self, result: ClaimResult) -> Result<(), CLIError> {
+        if result.error.is_empty() {
+            info!("released {} FIL to {}", result.released, result.address);
+        } else {
+            error!(
+                "failed to release funds to {}: {}",
+                result.address, result.error
+            );
+        }
+        self.writer.serialize(&result)?;
+        self.writer.flush()?;
+        self.results.push(result);
+        Ok(())
+    }
+
+    /// Logs a summary of the batch, failing if funds could not be released to
+    /// some of the addresses.
+    fn finish(self, path: &PathBuf) -> Result<(), CLIError> {
+        let failed = self
+            .results
+            .iter()
+            .filter(|result| !result.error.is_empty())
+            .count();
+        let released: Decimal = self.results.iter().map(|result| result.released).sum();
+        info!(
+            "released {} FIL to {} addresses, results written to {:?}",
+            released,
+            self.results.len() - failed,
+            path
+        );
+        if failed > 0 {
+            return Err(CLIError::Other(format!(
+                "failed to release funds to {} of {} addresses, see {:?}",
+                failed,
+                self.results.len(),
+                path
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Releases the funds of every address of `claim_csv`, sending the
+/// `releaseSelect` transactions with `client` one after the other. Anyone can
+/// release funds, which always go to the payee.
+///
+/// The amount released and the transaction hashes of each address are
+/// written to `results_csv`. A failure for one address does not stop the
+/// batch.
+pub async fn claim_batch<S: ::ethers::providers::Middleware + 'static>(
+    client: Arc<S>,
+    retries: usize,
+    gas_price: U256,
+    provider: &Provider<Http>,
+    factory_addr: &str,
+    claim_csv: &PathBuf,
+    results_csv: &PathBuf,
+    rpc_url: &str,
+) -> Result<(), CLIError> {
+    let addresses = read_claim_addresses(claim_csv)?;
+    let factory_eth_addr = filecoin_to_eth_address(factory_addr, rpc_url)
+        .await
+        .map_err(CLIError::rpc)?;
+    let mut writer = ClaimResultWriter::new(results_csv)?;
+
+    for address in addresses.iter() {
+        let mut progress = ReleaseProgress::default();
+        let result = async {
+            let releasable =
+                get_unreleased_payout_contracts(factory_addr, address, rpc_url, provider).await?;
+            release_all_selected_payouts(
+                client.clone(),
+                retries,
+                gas_price,
+                &factory_eth_addr,
+                address,
+                &releasable,
+                &mut progress,
+            )
+            .await
+        }
+        .await;
+        writer.write(ClaimResult::new(address, progress, result.err())?)?;
+    }
+
+    writer.finish(results_csv)
+}
+
+/// Releases the funds of every address of `claim_csv` with Filecoin messages
+/// signed by `signing_address`, which take consecutive nonces. With `wait`,
+/// each message must execute successfully before the next one is pushed.
+///
+/// The amount released and the message CIDs of each address are written to
+/// `results_csv`. A failure for one address does not stop the batch.
+pub async fn claim_batch_filecoin_signing(
+    provider: &Provider<Http>,
+    factory_addr: &str,
+    claim_csv: &PathBuf,
+    results_csv: &PathBuf,
+    signing_method: &SignatureMethod,
+    signing_address: &str,
+    rpc_url: &str,
+    wait: bool,
+) -> Result<(), CLIError> {
+    let addresses = read_claim_addresses(claim_csv)?;
+    let mut nonces = NonceAllocator::new(provider, signing_address).await?;
+    let mut writer = ClaimResultWriter::new(results_csv)?;
+
+    for address in addresses.iter() {
+        let mut progress = ReleaseProgress::default();
+        let result = async {
+            let releasable =
+                get_unreleased_payout_contracts(factory_addr, address, rpc_url, provider).await?;
+            release_all_selected_payouts_filecoin_signing(
+                provider,
+                factory_addr,
+                address,
+                &releasable,
+                signing_method,
+                signing_address,
+                &mut nonces,
+                rpc_url,
+                wait,
+                &mut progress,
+            )
+            .await
+        }
+        .await;
+        writer.write(ClaimResult::new(address, progress, result.err())?)?;
+    }
+
+    writer.finish(results_csv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_claim_addresses, ClaimResult};
+    use crate::utils::{ReleaseProgress, ATTO_FIL};
+    use assert_fs::prelude::*;
+    use ethers::types::U256;
+
+    #[test]
+    fn test_read_claim_addresses() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let csv = dir.child("claims.csv");
+        csv.write_str("Address\nf01\n f02 \n").unwrap();
+
+        let addresses = read_claim_addresses(&csv.path().to_path_buf()).unwrap();
+        assert_eq!(addresses, vec!["f01", "f02"]);
+    }
+
+    #[test]
+    fn test_claim_result() {
+        let progress = ReleaseProgress {
+            released: U256::from(3 * ATTO_FIL / 2),
+            transactions: vec![String::from("0x01"), String::from("0x02")],
+        };
+        let result = ClaimResult::new("f01", progress, Some("reverted".into())).unwrap();
+        assert_eq!(result.released.to_string(), "1.5");
+        assert_eq!(result.tx_hash, "0x01 0x02");
+        assert_eq!(result.error, "reverted");
+    }
+}
@@ -1,5 +1,6 @@
 use clap::{Parser, Subcommand};
 use contract_bindings::payout_factory_native_addr::PAYOUTFACTORYNATIVEADDR_ABI;
+use ethers::abi::Address;
 use ethers::core::k256::ecdsa::SigningKey;
 use ethers::middleware::SignerMiddleware;
 use ethers::providers::{Http, Middleware, Provider};
@@ -9,20 +10,44 @@ use fevm_utils::{
     filecoin_to_eth_address, get_ledger_signing_provider, get_provider, get_wallet_signing_provider,
 };
 
+use fil_actor_multisig::{
+    AddSignerParams, ChangeNumApprovalsThresholdParams, ConstructorParams, LockBalanceParams,
+    ProposeParams, RemoveSignerParams, SwapSignerParams,
+};
+use filecoin_signer::api::MessageParams;
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::address::Address as FilecoinAddress;
 use log::info;
 use serde::{Deserialize, Serialize};
-use std::error::Error;
 use std::fs::{self, read_to_string};
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 
+use crate::admins::{check_admin_revocation, list_admins, lookup_id, resolve_id};
+use crate::claim::{claim_batch, claim_batch_filecoin_signing};
+use crate::earnings::inspect_earnings_history;
+use crate::error::CLIError;
+use crate::gas::{set_gas_policy, GasPolicy};
+use crate::index::index_events;
+use crate::mpool::{bump_message, NonceAllocator, PendingMessageId};
+use crate::multisig::{
+    create_multisig, encode_factory_call, propose, propose_self_call, ExpectedPayouts,
+};
+use crate::receipt::{
+    wait_for_approval, wait_for_message, wait_for_multisig_creation, wait_for_proposal,
+};
+use crate::report::{factory_report, ReportFormat};
 use crate::utils::{
-    approve_payout, cancel_payout, deploy_factory_contract, fund_factory_contract,
-    generate_monthly_payout, get_pending_transaction_multisig, get_signing_method_and_address,
+    approve_payout, cancel_payout, deploy_factory_contract, fil_to_token_amount,
+    fund_factory_contract, fund_factory_contract_filecoin_signing, generate_monthly_payout,
+    get_pending_transaction, get_pending_transaction_multisig, get_signing_method_and_address,
     get_unreleased_payout_contracts, grant_admin, inspect_earnings, inspect_multisig, new_payout,
-    propose_payout, release_selected_payouts, release_selected_payouts_filecoin_signing,
-    revoke_admin, SigningOptions,
+    parse_fil_amount, parse_raw_payouts_from_csv, propose_payout, release_all_selected_payouts,
+    release_all_selected_payouts_filecoin_signing, revoke_admin, ReleaseProgress, SigningOptions,
 };
+use crate::validate::validate_csv;
+use crate::verify::verify_payout;
 
 #[allow(missing_docs)]
 #[derive(Parser, Debug, Clone, Deserialize, Serialize)]
@@ -43,22 +68,37 @@ pub struct Cli {
     /// Ledger account index in Bip44 Path.
     #[arg(long, default_value = "0")]
     ledger_account: u32,
-}
-
-#[derive(thiserror::Error, Debug)]
-pub enum CLIError {
-    #[error("contract failed to deploy")]
-    ContractNotDeployed,
+    /// Wait for Filecoin-signed messages to be executed and report their receipts.
+    #[arg(long, default_value_t = false)]
+    wait: bool,
+    #[command(flatten)]
+    gas: GasPolicy,
+    /// JSON file with the gas policy, used instead of the gas flags.
+    #[arg(
+        long,
+        conflicts_with_all = ["message_gas_limit_multiplier", "tx_gas_limit_multiplier", "max_fee", "fee_cap_ceiling", "premium_strategy", "premium"]
+    )]
+    gas_config: Option<PathBuf>,
 }
 
 impl Cli {
     /// Create a configuration
-    pub fn create() -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn create() -> Result<Self, CLIError> {
         Ok(Cli::parse())
     }
 
-    pub async fn run(self) -> Result<(), Box<dyn std::error::Error>> {
-        let provider = get_provider(&self.rpc_url)?;
+    pub async fn run(self) -> Result<(), CLIError> {
+        set_gas_policy(match &self.gas_config {
+            Some(gas_config) => GasPolicy::load(gas_config)?,
+            None => self.gas.clone(),
+        })?;
+
+        // commands that do not need a node
+        if let Commands::ValidateCsv { payout_csv } = &self.command {
+            return validate_csv(payout_csv);
+        }
+
+        let provider = get_provider(&self.rpc_url).map_err(CLIError::rpc)?;
         let gas_price = provider.get_gas_price().await?;
         let chain_id = provider.get_chainid().await?;
         info!("current gas price: {:#?}", gas_price);
@@ -67,11 +107,13 @@ impl Cli {
         async fn get_wallet(
             secret: PathBuf,
             provider: Provider<Http>,
-        ) -> Result<Arc<SignerMiddleware<Arc<Provider<Http>>, Wallet<SigningKey>>>, Box<dyn Error>>
+        ) -> Result<Arc<SignerMiddleware<Arc<Provider<Http>>, Wallet<SigningKey>>>, CLIError>
         {
             let mnemonic = read_to_string(secret)?;
             let client: SignerMiddleware<Arc<Provider<Http>>, Wallet<SigningKey>> =
-                get_wallet_signing_provider(provider, &mnemonic).await?;
+                get_wallet_signing_provider(provider, &mnemonic)
+                    .await
+                    .map_err(CLIError::signing)?;
             let client = Arc::new(client);
             Ok(client)
         }
@@ -88,7 +130,9 @@ impl Cli {
                     )
                     .await?;
                 } else {
-                    let client = get_ledger_signing_provider(provider, chain_id.as_u64()).await?;
+                    let client = get_ledger_signing_provider(provider, chain_id.as_u64())
+                        .await
+                        .map_err(CLIError::ledger)?;
                     let client = Arc::new(client);
                     deploy_factory_contract(
                         client.clone(),
@@ -104,6 +148,9 @@ impl Cli {
                 payout_csv,
                 db_deploy,
                 date,
+                resume,
+                dry_run,
+                plan,
             } => {
                 if self.secret.is_some() {
                     let client = get_wallet(self.secret.unwrap(), provider).await?;
@@ -115,10 +162,15 @@ impl Cli {
                         payout_csv,
                         db_deploy,
                         date,
+                        resume,
+                        *dry_run,
+                        plan,
                     )
                     .await?;
                 } else {
-                    let client = get_ledger_signing_provider(provider, chain_id.as_u64()).await?;
+                    let client = get_ledger_signing_provider(provider, chain_id.as_u64())
+                        .await
+                        .map_err(CLIError::ledger)?;
                     let client = Arc::new(client);
                     new_payout(
                         client.clone(),
@@ -128,6 +180,9 @@ impl Cli {
                         payout_csv,
                         db_deploy,
                         date,
+                        resume,
+                        *dry_run,
+                        plan,
                     )
                     .await?;
                 }
@@ -138,85 +193,179 @@ impl Cli {
                 addr_to_claim,
                 method,
             } => {
-                let releasable_contract_indices = get_unreleased_payout_contracts(
+                let releasable = get_unreleased_payout_contracts(
                     &factory_addr,
                     &addr_to_claim,
                     &self.rpc_url,
                     &provider.clone(),
                 )
-                .await
-                .unwrap();
+                .await?;
+                if releasable.is_empty() {
+                    info!("no funds to claim for {}", addr_to_claim);
+                    return Ok(());
+                }
+                let mut progress = ReleaseProgress::default();
                 match method {
                     Some(option) => {
                         let (signing_method, signer_address) =
                             get_signing_method_and_address(option, self.ledger_account.clone())
-                                .await
-                                .unwrap();
+                                .await?;
 
-                        release_selected_payouts_filecoin_signing(
+                        let mut nonces = NonceAllocator::new(&provider, &signer_address).await?;
+                        release_all_selected_payouts_filecoin_signing(
                             &provider.clone(),
-                            factory_addr,
-                            addr_to_claim,
-                            releasable_contract_indices.clone(),
+                            &factory_addr,
+                            &addr_to_claim,
+                            &releasable,
                             &signing_method,
                             &signer_address,
+                            &mut nonces,
                             &self.rpc_url,
+                            self.wait,
+                            &mut progress,
                         )
-                        .await?
+                        .await?;
                     }
                     None => {
                         let factory_eth_addr =
-                            filecoin_to_eth_address(&factory_addr, &self.rpc_url).await?;
+                            filecoin_to_eth_address(&factory_addr, &self.rpc_url)
+                                .await
+                                .map_err(CLIError::rpc)?;
                         if self.secret.is_some() {
                             let client = get_wallet(self.secret.unwrap(), provider).await?;
-                            release_selected_payouts(
+                            release_all_selected_payouts(
                                 client.clone(),
                                 self.retries,
                                 gas_price,
                                 &factory_eth_addr,
-                                addr_to_claim,
-                                releasable_contract_indices.clone(),
+                                &addr_to_claim,
+                                &releasable,
+                                &mut progress,
                             )
                             .await?;
                         } else {
-                            let client =
-                                get_ledger_signing_provider(provider, chain_id.as_u64()).await?;
+                            let client = get_ledger_signing_provider(provider, chain_id.as_u64())
+                                .await
+                                .map_err(CLIError::ledger)?;
                             let client = Arc::new(client);
-                            release_selected_payouts(
+                            release_all_selected_payouts(
                                 client.clone(),
                                 self.retries,
                                 gas_price,
                                 &factory_eth_addr,
-                                addr_to_claim,
-                                releasable_contract_indices.clone(),
+                                &addr_to_claim,
+                                &releasable,
+                                &mut progress,
                             )
                             .await?;
                         }
                     }
                 }
             }
+            Commands::ClaimBatch {
+                factory_addr,
+                claim_csv,
+                results,
+                method,
+            } => match method {
+                Some(option) => {
+                    let (signing_method, signer_address) =
+                        get_signing_method_and_address(option, self.ledger_account.clone()).await?;
+
+                    claim_batch_filecoin_signing(
+                        &provider,
+                        factory_addr,
+                        claim_csv,
+                        results,
+                        &signing_method,
+                        &signer_address,
+                        &self.rpc_url,
+                        self.wait,
+                    )
+                    .await?;
+                }
+                None => {
+                    if self.secret.is_some() {
+                        let client = get_wallet(self.secret.unwrap(), provider.clone()).await?;
+                        claim_batch(
+                            client.clone(),
+                            self.retries,
+                            gas_price,
+                            &provider,
+                            factory_addr,
+                            claim_csv,
+                            results,
+                            &self.rpc_url,
+                        )
+                        .await?;
+                    } else {
+                        let client =
+                            get_ledger_signing_provider(provider.clone(), chain_id.as_u64())
+                                .await
+                                .map_err(CLIError::ledger)?;
+                        let client = Arc::new(client);
+                        claim_batch(
+                            client.clone(),
+                            self.retries,
+                            gas_price,
+                            &provider,
+                            factory_addr,
+                            claim_csv,
+                            results,
+                            &self.rpc_url,
+                        )
+                        .await?;
+                    }
+                }
+            },
             Commands::Fund {
                 factory_addr,
                 amount,
+                method,
             } => {
-                fund_factory_contract(
-                    factory_addr,
-                    amount,
-                    self.secret,
-                    provider,
-                    self.retries,
-                    gas_price,
-                )
-                .await
+                let amount = parse_fil_amount(amount)?;
+                match method {
+                    Some(option) => {
+                        let (signing_method, signer_address) =
+                            get_signing_method_and_address(option, self.ledger_account.clone())
+                                .await?;
+
+                        let mut nonces = NonceAllocator::new(&provider, &signer_address).await?;
+                        let cid = fund_factory_contract_filecoin_signing(
+                            &provider,
+                            factory_addr,
+                            &amount,
+                            &signing_method,
+                            &signer_address,
+                            &mut nonces,
+                        )
+                        .await?;
+                        if self.wait {
+                            wait_for_message(&provider, &cid).await?.ensure_success()?;
+                        }
+                    }
+                    None => {
+                        fund_factory_contract(
+                            factory_addr,
+                            &amount,
+                            self.secret,
+                            provider,
+                            self.retries,
+                            gas_price,
+                        )
+                        .await?;
+                    }
+                }
             }
             Commands::WriteAbi { path } => {
-                let string_abi = ser::to_string(&PAYOUTFACTORYNATIVEADDR_ABI.clone())?;
+                let string_abi = ser::to_string(&PAYOUTFACTORYNATIVEADDR_ABI.clone())
+                    .map_err(|error| CLIError::Other(error.to_string()))?;
                 fs::write(&path, string_abi)?;
             }
             Commands::GenerateMonthlyPayout {
                 date,
                 factory_address,
-            } => generate_monthly_payout(date, factory_address).await,
+            } => generate_monthly_payout(date, factory_address).await?,
             Commands::MultisigInspect { actor_id } => {
                 inspect_multisig(&provider, actor_id).await?;
             }
@@ -227,13 +376,13 @@ impl Cli {
                 db_deploy,
                 date,
                 method,
+                dry_run,
+                plan,
             } => {
                 let (signing_method, signer_address) =
-                    get_signing_method_and_address(method, self.ledger_account.clone())
-                        .await
-                        .unwrap();
+                    get_signing_method_and_address(method, self.ledger_account.clone()).await?;
 
-                propose_payout(
+                let cids = propose_payout(
                     actor_address,
                     receiver_address,
                     date,
@@ -243,8 +392,15 @@ impl Cli {
                     &self.rpc_url,
                     signing_method,
                     &signer_address,
+                    *dry_run,
+                    plan,
                 )
                 .await?;
+                if self.wait {
+                    for cid in cids.iter() {
+                        wait_for_proposal(&provider, cid).await?;
+                    }
+                }
             }
             Commands::CancelPayout {
                 actor_address,
@@ -252,18 +408,49 @@ impl Cli {
                 method,
             } => {
                 let (signing_method, signer_address) =
-                    get_signing_method_and_address(method, self.ledger_account.clone())
-                        .await
-                        .unwrap();
+                    get_signing_method_and_address(method, self.ledger_account.clone()).await?;
 
-                cancel_payout(
+                let mut nonces = NonceAllocator::new(&provider, &signer_address).await?;
+                let cid = cancel_payout(
                     actor_address,
                     &provider,
                     &transaction_id,
                     &signing_method,
                     &signer_address,
+                    &mut nonces,
                 )
                 .await?;
+                if self.wait {
+                    wait_for_message(&provider, &cid).await?.ensure_success()?;
+                }
+            }
+            Commands::BumpMessage {
+                cid,
+                nonce,
+                from,
+                method,
+            } => {
+                let (signing_method, signer_address) =
+                    get_signing_method_and_address(method, self.ledger_account.clone()).await?;
+
+                let id = match (cid, nonce) {
+                    (Some(cid), _) => PendingMessageId::Cid(cid.clone()),
+                    (None, Some(nonce)) => PendingMessageId::Nonce {
+                        sender: FilecoinAddress::from_str(
+                            from.as_ref().unwrap_or(&signer_address),
+                        )?,
+                        nonce: *nonce,
+                    },
+                    (None, None) => {
+                        return Err(CLIError::InvalidInput(String::from(
+                            "either --cid or --nonce is required",
+                        )))
+                    }
+                };
+                let cid = bump_message(&provider, &id, &signing_method, &signer_address).await?;
+                if self.wait {
+                    wait_for_message(&provider, &cid).await?.ensure_success()?;
+                }
             }
             Commands::CancelAll {
                 actor_address,
@@ -271,57 +458,107 @@ impl Cli {
             } => {
                 let tx = get_pending_transaction_multisig(&provider, actor_address).await?;
                 let (signing_method, signer_address) =
-                    get_signing_method_and_address(method, self.ledger_account.clone())
-                        .await
-                        .unwrap();
+                    get_signing_method_and_address(method, self.ledger_account.clone()).await?;
+                let mut nonces = NonceAllocator::new(&provider, &signer_address).await?;
                 for transaction in tx.iter() {
-                    cancel_payout(
+                    let cid = cancel_payout(
                         actor_address,
                         &provider,
                         &format!("{}", transaction.id),
                         &signing_method,
                         &signer_address,
+                        &mut nonces,
                     )
                     .await?;
+                    if self.wait {
+                        wait_for_message(&provider, &cid).await?.ensure_success()?;
+                    }
                 }
             }
             Commands::ApproveNewPayout {
                 actor_address,
                 transaction_id,
                 method,
+                expect_csv,
+                factory_address,
             } => {
-                let (signing_method, signer_address) =
-                    get_signing_method_and_address(method, self.ledger_account.clone())
+                let transaction =
+                    get_pending_transaction(&provider, actor_address, transaction_id).await?;
+                if let (Some(expect_csv), Some(factory_address)) = (expect_csv, factory_address) {
+                    validate_csv(expect_csv)?;
+                    let (payees, shares) = parse_raw_payouts_from_csv(expect_csv).await?;
+                    let factory_id = resolve_id(&provider, factory_address).await?;
+                    let mut expected = ExpectedPayouts::new(&factory_id, &payees, &shares)?;
+                    // let the other pending proposals claim their batches
+                    // first, so that a batch proposed twice is refused;
+                    // proposals to addresses with no actor are no payouts
+                    let pending =
+                        get_pending_transaction_multisig(&provider, actor_address).await?;
+                    for other in pending.iter().filter(|other| other.id != transaction.id) {
+                        if let Some(to_id) = lookup_id(&provider, &other.to).await {
+                            let _ = expected.verify(other, &to_id);
+                        }
+                    }
+                    // a target with no actor is reported as not the factory
+                    let to_id = lookup_id(&provider, &transaction.to)
                         .await
-                        .unwrap();
+                        .unwrap_or_else(|| transaction.to.clone());
+                    expected.verify(&transaction, &to_id)?;
+                }
+                let (signing_method, signer_address) =
+                    get_signing_method_and_address(method, self.ledger_account.clone()).await?;
 
-                approve_payout(
+                let mut nonces = NonceAllocator::new(&provider, &signer_address).await?;
+                let cid = approve_payout(
                     &actor_address,
                     &provider,
                     &signing_method,
                     &signer_address,
-                    transaction_id,
+                    &transaction,
+                    &mut nonces,
                 )
                 .await?;
+                if self.wait {
+                    wait_for_approval(&provider, &cid).await?;
+                }
             }
             Commands::ApproveAll {
                 actor_address,
                 method,
+                expect_csv,
+                factory_address,
             } => {
                 let tx = get_pending_transaction_multisig(&provider, actor_address).await?;
+                // check every proposal before approving any of them
+                if let (Some(expect_csv), Some(factory_address)) = (expect_csv, factory_address) {
+                    validate_csv(expect_csv)?;
+                    let (payees, shares) = parse_raw_payouts_from_csv(expect_csv).await?;
+                    let factory_id = resolve_id(&provider, factory_address).await?;
+                    let mut expected = ExpectedPayouts::new(&factory_id, &payees, &shares)?;
+                    for transaction in tx.iter() {
+                        // a target with no actor is reported as not the factory
+                        let to_id = lookup_id(&provider, &transaction.to)
+                            .await
+                            .unwrap_or_else(|| transaction.to.clone());
+                        expected.verify(transaction, &to_id)?;
+                    }
+                }
                 let (signing_method, signer_address) =
-                    get_signing_method_and_address(method, self.ledger_account.clone())
-                        .await
-                        .unwrap();
+                    get_signing_method_and_address(method, self.ledger_account.clone()).await?;
+                let mut nonces = NonceAllocator::new(&provider, &signer_address).await?;
                 for transaction in tx.iter() {
-                    approve_payout(
+                    let cid = approve_payout(
                         &actor_address,
                         &provider,
                         &signing_method,
                         &signer_address,
-                        &format!("{}", transaction.id),
+                        transaction,
+                        &mut nonces,
                     )
                     .await?;
+                    if self.wait {
+                        wait_for_approval(&provider, &cid).await?;
+                    }
                 }
             }
             Commands::GrantAdmin {
@@ -340,7 +577,9 @@ impl Cli {
                     )
                     .await?;
                 } else {
-                    let client = get_ledger_signing_provider(provider, chain_id.as_u64()).await?;
+                    let client = get_ledger_signing_provider(provider, chain_id.as_u64())
+                        .await
+                        .map_err(CLIError::ledger)?;
                     let client = Arc::new(client);
                     grant_admin(
                         client.clone(),
@@ -356,7 +595,20 @@ impl Cli {
             Commands::RevokeAdmin {
                 address,
                 factory_addr,
+                from_block,
+                i_understand_lockout,
             } => {
+                let account = filecoin_to_eth_address(address, &self.rpc_url)
+                    .await
+                    .map_err(CLIError::rpc)?;
+                check_admin_revocation(
+                    &provider,
+                    factory_addr,
+                    Address::from_str(&account)?,
+                    *from_block,
+                    *i_understand_lockout,
+                )
+                .await?;
                 if self.secret.is_some() {
                     let client = get_wallet(self.secret.unwrap(), provider).await?;
                     revoke_admin(
@@ -369,7 +621,9 @@ impl Cli {
                     )
                     .await?;
                 } else {
-                    let client = get_ledger_signing_provider(provider, chain_id.as_u64()).await?;
+                    let client = get_ledger_signing_provider(provider, chain_id.as_u64())
+                        .await
+                        .map_err(CLIError::ledger)?;
                     let client = Arc::new(client);
                     revoke_admin(
                         client.clone(),
@@ -382,20 +636,421 @@ impl Cli {
                     .await?;
                 }
             }
+            Commands::Multisig {
+                command:
+                    MultisigCommands::Create {
+                        signers,
+                        threshold,
+                        unlock_duration,
+                        start_epoch,
+                        value,
+                        method,
+                    },
+            } => {
+                let params = ConstructorParams {
+                    signers: signers
+                        .iter()
+                        .map(|signer| FilecoinAddress::from_str(signer))
+                        .collect::<Result<Vec<FilecoinAddress>, _>>()?,
+                    num_approvals_threshold: *threshold,
+                    unlock_duration: *unlock_duration,
+                    start_epoch: *start_epoch,
+                };
+                let value = fil_to_token_amount(&parse_fil_amount(value)?)?;
+                let (signing_method, signer_address) =
+                    get_signing_method_and_address(method, self.ledger_account.clone()).await?;
+
+                let mut nonces = NonceAllocator::new(&provider, &signer_address).await?;
+                let cid = create_multisig(
+                    &provider,
+                    &signing_method,
+                    &signer_address,
+                    params,
+                    value,
+                    &mut nonces,
+                )
+                .await?;
+                // the addresses of the new multisig are only known once it executed
+                wait_for_multisig_creation(&provider, &cid).await?;
+            }
+            Commands::Multisig {
+                command:
+                    MultisigCommands::Propose {
+                        actor_address,
+                        to,
+                        value,
+                        method_num,
+                        params,
+                        function,
+                        args,
+                        method,
+                    },
+            } => {
+                let (method_num, params) = match function {
+                    Some(function) => (
+                        fil_actor_evm::Method::InvokeContract as u64,
+                        encode_factory_call(function, args)?,
+                    ),
+                    None => (
+                        method_num.unwrap_or(0),
+                        match params {
+                            Some(params) => {
+                                RawBytes::new(hex::decode(params.trim_start_matches("0x"))?)
+                            }
+                            None => RawBytes::default(),
+                        },
+                    ),
+                };
+                let params = ProposeParams {
+                    to: FilecoinAddress::from_str(to)?,
+                    value: fil_to_token_amount(&parse_fil_amount(value)?)?,
+                    method: method_num,
+                    params,
+                };
+                let (signing_method, signer_address) =
+                    get_signing_method_and_address(method, self.ledger_account.clone()).await?;
+
+                let mut nonces = NonceAllocator::new(&provider, &signer_address).await?;
+                let cid = propose(
+                    &provider,
+                    actor_address,
+                    &signing_method,
+                    &signer_address,
+                    params,
+                    &mut nonces,
+                )
+                .await?;
+                if self.wait {
+                    wait_for_proposal(&provider, &cid).await?;
+                }
+            }
+            Commands::Multisig { command } => {
+                let (actor_address, method, params) = command.proposal()?;
+                let (signing_method, signer_address) =
+                    get_signing_method_and_address(method, self.ledger_account.clone()).await?;
+
+                let mut nonces = NonceAllocator::new(&provider, &signer_address).await?;
+                let cid = propose_self_call(
+                    &provider,
+                    actor_address,
+                    &signing_method,
+                    &signer_address,
+                    params,
+                    &mut nonces,
+                )
+                .await?;
+                if self.wait {
+                    wait_for_proposal(&provider, &cid).await?;
+                }
+            }
+            // run before connecting to a node
+            Commands::ValidateCsv { .. } => {}
+            Commands::VerifyPayout {
+                factory_address,
+                payout_csv,
+                start_index,
+                report,
+            } => {
+                let factory_eth_addr = filecoin_to_eth_address(&factory_address, &self.rpc_url)
+                    .await
+                    .map_err(CLIError::rpc)?;
+                verify_payout(
+                    &provider,
+                    &factory_eth_addr,
+                    payout_csv,
+                    *start_index,
+                    report,
+                )
+                .await?;
+            }
+            Commands::Index {
+                factory_address,
+                db,
+                from_block,
+            } => {
+                let factory_eth_addr = filecoin_to_eth_address(&factory_address, &self.rpc_url)
+                    .await
+                    .map_err(CLIError::rpc)?;
+                index_events(&provider, &factory_eth_addr, db, *from_block).await?;
+            }
+            Commands::FactoryReport {
+                factory_address,
+                db,
+                from_block,
+                format,
+                output,
+            } => {
+                let factory_eth_addr = filecoin_to_eth_address(&factory_address, &self.rpc_url)
+                    .await
+                    .map_err(CLIError::rpc)?;
+                factory_report(
+                    &provider,
+                    &factory_eth_addr,
+                    db,
+                    *from_block,
+                    *format,
+                    output,
+                )
+                .await?;
+            }
+            Commands::ListAdmins {
+                factory_address,
+                from_block,
+            } => {
+                let factory_eth_addr = filecoin_to_eth_address(&factory_address, &self.rpc_url)
+                    .await
+                    .map_err(CLIError::rpc)?;
+                list_admins(&provider, &factory_eth_addr, *from_block).await?;
+            }
             Commands::InspectEarnings {
                 address,
                 factory_address,
+                history,
+                db,
+                from_block,
             } => {
-                let factory_eth_addr =
-                    filecoin_to_eth_address(&factory_address, &self.rpc_url).await?;
-                let provider = get_provider(&self.rpc_url).unwrap();
-                inspect_earnings(&provider, address, &factory_eth_addr).await;
+                let factory_eth_addr = filecoin_to_eth_address(&factory_address, &self.rpc_url)
+                    .await
+                    .map_err(CLIError::rpc)?;
+                if *history {
+                    inspect_earnings_history(
+                        &provider,
+                        address,
+                        &factory_eth_addr,
+                        db,
+                        *from_block,
+                    )
+                    .await?;
+                } else {
+                    inspect_earnings(&provider, address, &factory_eth_addr).await?;
+                }
             }
         }
         Ok(())
     }
 }
 
+/// Multisig creation, and changes each proposed to the multisig as a call to
+/// itself that executes once enough signers approved it.
+#[allow(missing_docs)]
+#[derive(Debug, Subcommand, Clone, Deserialize, Serialize)]
+pub enum MultisigCommands {
+    /// Creates a new multisig actor and reports its addresses
+    #[command(arg_required_else_help = true)]
+    Create {
+        /// Filecoin addresses of the signers, comma separated
+        #[arg(long, value_delimiter = ',', required = true)]
+        signers: Vec<String>,
+        /// Number of approvals transactions need
+        #[arg(long)]
+        threshold: u64,
+        /// Number of epochs until the initial balance is fully unlocked
+        #[arg(long, default_value_t = 0)]
+        unlock_duration: i64,
+        /// Epoch the unlocking of the initial balance starts at
+        #[arg(long, default_value_t = 0)]
+        start_epoch: i64,
+        /// Initial balance of the multisig, in FIL
+        #[arg(long, default_value = "0")]
+        value: String,
+        /// Signing Method for the command.
+        #[arg(long, default_value = "local", value_enum)]
+        method: SigningOptions,
+    },
+    /// Proposes adding a signer
+    #[command(arg_required_else_help = true)]
+    AddSigner {
+        /// Multisig Filecoin Actor Id or Address
+        #[arg(short = 'A', long)]
+        actor_address: String,
+        /// Filecoin address of the new signer
+        #[arg(long)]
+        signer: String,
+        /// Also increase the approval threshold by one.
+        #[arg(long, default_value_t = false)]
+        increase_threshold: bool,
+        /// Signing Method for the command.
+        #[arg(long, default_value = "local", value_enum)]
+        method: SigningOptions,
+    },
+    /// Proposes removing a signer
+    #[command(arg_required_else_help = true)]
+    RemoveSigner {
+        /// Multisig Filecoin Actor Id or Address
+        #[arg(short = 'A', long)]
+        actor_address: String,
+        /// Filecoin address of the signer to remove
+        #[arg(long)]
+        signer: String,
+        /// Also decrease the approval threshold by one.
+        #[arg(long, default_value_t = false)]
+        decrease_threshold: bool,
+        /// Signing Method for the command.
+        #[arg(long, default_value = "local", value_enum)]
+        method: SigningOptions,
+    },
+    /// Proposes replacing a signer with another address
+    #[command(arg_required_else_help = true)]
+    SwapSigner {
+        /// Multisig Filecoin Actor Id or Address
+        #[arg(short = 'A', long)]
+        actor_address: String,
+        /// Filecoin address of the signer to replace
+        #[arg(long)]
+        from: String,
+        /// Filecoin address of the replacing signer
+        #[arg(long)]
+        to: String,
+        /// Signing Method for the command.
+        #[arg(long, default_value = "local", value_enum)]
+        method: SigningOptions,
+    },
+    /// Proposes changing the number of approvals transactions need
+    #[command(arg_required_else_help = true)]
+    ChangeThreshold {
+        /// Multisig Filecoin Actor Id or Address
+        #[arg(short = 'A', long)]
+        actor_address: String,
+        /// New number of approvals
+        #[arg(long)]
+        threshold: u64,
+        /// Signing Method for the command.
+        #[arg(long, default_value = "local", value_enum)]
+        method: SigningOptions,
+    },
+    /// Proposes any transaction: a transfer of FIL, a call of an actor method
+    /// with raw params, or a call of a PayoutFactory function
+    #[command(arg_required_else_help = true)]
+    Propose {
+        /// Multisig Filecoin Actor Id or Address
+        #[arg(short = 'A', long)]
+        actor_address: String,
+        /// Filecoin address the transaction is sent to
+        #[arg(long)]
+        to: String,
+        /// FIL sent with the transaction
+        #[arg(long, default_value = "0")]
+        value: String,
+        /// Method number called on the target. Defaults to a plain transfer.
+        #[arg(long, conflicts_with = "function")]
+        method_num: Option<u64>,
+        /// Hex encoded params of the method.
+        #[arg(long, requires = "method_num")]
+        params: Option<String>,
+        /// PayoutFactory function to call on the target, e.g. grantRole.
+        #[arg(long)]
+        function: Option<String>,
+        /// Arguments of the PayoutFactory function, in order.
+        #[arg(long = "arg", requires = "function")]
+        args: Vec<String>,
+        /// Signing Method for the command.
+        #[arg(long, default_value = "local", value_enum)]
+        method: SigningOptions,
+    },
+    /// Proposes locking part of the multisig balance, vesting linearly over
+    /// the unlock duration
+    #[command(arg_required_else_help = true)]
+    LockBalance {
+        /// Multisig Filecoin Actor Id or Address
+        #[arg(short = 'A', long)]
+        actor_address: String,
+        /// Epoch the vesting starts at
+        #[arg(long)]
+        start_epoch: i64,
+        /// Number of epochs until the amount is fully unlocked
+        #[arg(long)]
+        unlock_duration: i64,
+        /// Amount to lock, in FIL
+        #[arg(long)]
+        amount: String,
+        /// Signing Method for the command.
+        #[arg(long, default_value = "local", value_enum)]
+        method: SigningOptions,
+    },
+}
+
+impl MultisigCommands {
+    /// Multisig actor the change is proposed to, signing method and params of
+    /// the multisig method the multisig calls on itself.
+    fn proposal(&self) -> Result<(&str, &SigningOptions, MessageParams), CLIError> {
+        let proposal = match self {
+            MultisigCommands::Create { .. } | MultisigCommands::Propose { .. } => {
+                return Err(CLIError::InvalidInput(String::from(
+                    "only signer, threshold and lock-balance changes are proposed as self calls",
+                )))
+            }
+            MultisigCommands::AddSigner {
+                actor_address,
+                signer,
+                increase_threshold,
+                method,
+            } => (
+                actor_address,
+                method,
+                MessageParams::AddSignerParams(AddSignerParams {
+                    signer: FilecoinAddress::from_str(signer)?,
+                    increase: *increase_threshold,
+                }),
+            ),
+            MultisigCommands::RemoveSigner {
+                actor_address,
+                signer,
+                decrease_threshold,
+                method,
+            } => (
+                actor_address,
+                method,
+                MessageParams::RemoveSignerParams(RemoveSignerParams {
+                    signer: FilecoinAddress::from_str(signer)?,
+                    decrease: *decrease_threshold,
+                }),
+            ),
+            MultisigCommands::SwapSigner {
+                actor_address,
+                from,
+                to,
+                method,
+            } => (
+                actor_address,
+                method,
+                MessageParams::SwapSignerParams(SwapSignerParams {
+                    from: FilecoinAddress::from_str(from)?,
+                    to: FilecoinAddress::from_str(to)?,
+                }),
+            ),
+            MultisigCommands::ChangeThreshold {
+                actor_address,
+                threshold,
+                method,
+            } => (
+                actor_address,
+                method,
+                MessageParams::ChangeNumApprovalsThresholdParams(
+                    ChangeNumApprovalsThresholdParams {
+                        new_threshold: *threshold,
+                    },
+                ),
+            ),
+            MultisigCommands::LockBalance {
+                actor_address,
+                start_epoch,
+                unlock_duration,
+                amount,
+                method,
+            } => (
+                actor_address,
+                method,
+                MessageParams::LockBalanceParams(LockBalanceParams {
+                    start_epoch: *start_epoch,
+                    unlock_duration: *unlock_duration,
+                    amount: fil_to_token_amount(&parse_fil_amount(amount)?)?,
+                }),
+            ),
+        };
+        Ok((proposal.0.as_str(), proposal.1, proposal.2))
+    }
+}
+
 #[allow(missing_docs)]
 #[derive(Debug, Subcommand, Clone, Deserialize, Serialize)]
 pub enum Commands {
@@ -415,8 +1070,18 @@ pub enum Commands {
         // Date for the payout period month.
         #[arg(short = 'D', long, default_value = "")]
         date: String,
+        /// Journal of a previous run to resume. Batches confirmed on chain are skipped.
+        #[arg(long, conflicts_with = "dry_run")]
+        resume: Option<PathBuf>,
+        /// Builds and estimates every batch without sending anything.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// File to write the dry-run plan to, as JSON.
+        #[arg(long, requires = "dry_run")]
+        plan: Option<PathBuf>,
     },
-    /// Claims all available funds for a given address
+    /// Claims all available funds for a given address, releasing at most 12
+    /// payout contracts per transaction
     #[command(arg_required_else_help = true)]
     Claim {
         /// PayoutFactory Filecoin address.
@@ -428,15 +1093,38 @@ pub enum Commands {
         #[arg(short = 'M', long, required = false)]
         method: Option<SigningOptions>,
     },
+    /// Releases the funds of every address of a CSV, on behalf of node
+    /// operators who cannot claim themselves
+    #[command(arg_required_else_help = true)]
+    ClaimBatch {
+        /// PayoutFactory Filecoin address.
+        #[arg(short = 'F', long)]
+        factory_addr: String,
+        /// CSV with an `Address` column listing the addresses to release funds
+        /// to.
+        #[arg(short = 'C', long)]
+        claim_csv: PathBuf,
+        /// Path to write the amount released and transaction hashes of each
+        /// address to, as a CSV.
+        #[arg(short = 'O', long)]
+        results: PathBuf,
+        #[arg(short = 'M', long, required = false)]
+        method: Option<SigningOptions>,
+    },
     /// Fund a factory contract
     #[command(arg_required_else_help = true)]
     Fund {
-        /// PayoutFactory ethereum address.
+        /// PayoutFactory ethereum address, or its Filecoin address when
+        /// signing with --method.
         #[arg(short = 'F', long)]
         factory_addr: String,
-        // Amount to send
+        /// Amount to send, in FIL.
         #[arg(short = 'A', long)]
-        amount: i128,
+        amount: String,
+        /// Signs a transfer to the factory's f410 address with a Filecoin
+        /// signing method instead of the --secret wallet.
+        #[arg(short = 'M', long, required = false)]
+        method: Option<SigningOptions>,
     },
     /// Path to write the abi
     WriteAbi {
@@ -461,6 +1149,60 @@ pub enum Commands {
         #[arg(short = 'A', long)]
         actor_id: String,
     },
+    /// Creates multisig actors and proposes changes to their signers and settings
+    Multisig {
+        #[command(subcommand)]
+        command: MultisigCommands,
+    },
+    /// Indexes the events of a payout factory and its payout contracts into a
+    /// local SQLite file, resuming from the last indexed block
+    #[command(arg_required_else_help = true)]
+    Index {
+        /// PayoutFactory Filecoin address.
+        #[arg(short = 'F', long)]
+        factory_address: String,
+        /// SQLite file to index the events into.
+        #[arg(long, default_value = "./events.sqlite")]
+        db: PathBuf,
+        /// Block to start indexing from when the file has no cursor for the
+        /// factory yet, e.g. the block the factory was deployed at.
+        #[arg(long, default_value_t = 0)]
+        from_block: u64,
+    },
+    /// Reports the funds deployed, claimed and unclaimed per month across the
+    /// payout contracts of a factory, and its idle balance
+    #[command(arg_required_else_help = true)]
+    FactoryReport {
+        /// PayoutFactory Filecoin address.
+        #[arg(short = 'F', long)]
+        factory_address: String,
+        /// SQLite event index the months of the payout contracts are read
+        /// from, brought up to date first.
+        #[arg(long, default_value = "./events.sqlite")]
+        db: PathBuf,
+        /// Block to start indexing from when the index has no cursor for the
+        /// factory yet.
+        #[arg(long, default_value_t = 0)]
+        from_block: u64,
+        /// Format of the report.
+        #[arg(long, default_value = "table", value_enum)]
+        format: ReportFormat,
+        /// File to write the report to, instead of stdout.
+        #[arg(short = 'o', long)]
+        output: Option<PathBuf>,
+    },
+    /// Lists the current admins of a payout factory, from its role events
+    #[command(arg_required_else_help = true)]
+    ListAdmins {
+        /// PayoutFactory Filecoin address.
+        #[arg(short = 'F', long)]
+        factory_address: String,
+        /// Block to replay the role events from, e.g. the block the factory
+        /// was deployed at. Required, as replaying from genesis takes
+        /// thousands of queries.
+        #[arg(long)]
+        from_block: u64,
+    },
     /// Returns Payout stats for a given node filecoin address
     #[command(arg_required_else_help = true)]
     InspectEarnings {
@@ -470,6 +1212,42 @@ pub enum Commands {
         /// Multisig Filecoin Actor Id or Address
         #[arg(short = 'F', long)]
         factory_address: String,
+        /// Lists every payout contract the address is a payee of, with its
+        /// payout month and release transactions, instead of the totals.
+        #[arg(long, default_value_t = false)]
+        history: bool,
+        /// SQLite event index the payout months and release transactions are
+        /// read from, brought up to date first. Used with --history.
+        #[arg(long, default_value = "./events.sqlite")]
+        db: PathBuf,
+        /// Block to start indexing from when the index has no cursor for the
+        /// factory yet. Used with --history.
+        #[arg(long, default_value_t = 0)]
+        from_block: u64,
+    },
+    /// Checks a payout CSV for invalid, duplicate or non-positive rows before it is deployed
+    #[command(arg_required_else_help = true)]
+    ValidateCsv {
+        /// Payout CSV to validate.
+        #[arg(short = 'P', long)]
+        payout_csv: PathBuf,
+    },
+    /// Reconciles a payout CSV against the payout contracts deployed by a factory
+    #[command(arg_required_else_help = true)]
+    VerifyPayout {
+        /// PayoutFactory Filecoin address.
+        #[arg(short = 'F', long)]
+        factory_address: String,
+        /// Payout CSV to reconcile.
+        #[arg(short = 'P', long)]
+        payout_csv: PathBuf,
+        /// Index of the first payout contract to inspect. Earlier payout
+        /// contracts belong to previous payouts.
+        #[arg(long, default_value = "0")]
+        start_index: usize,
+        /// Path to write the full reconciliation report as a CSV.
+        #[arg(long)]
+        report: Option<PathBuf>,
     },
     /// Proposes a new payout deployment to a multisig address and
     /// a factory contract address
@@ -492,6 +1270,12 @@ pub enum Commands {
         /// Signing Method for the command.
         #[arg(long, default_value = "local", value_enum)]
         method: SigningOptions,
+        /// Builds and estimates every batch without sending anything.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// File to write the dry-run plan to, as JSON.
+        #[arg(long, requires = "dry_run")]
+        plan: Option<PathBuf>,
     },
     /// Cancels a proposed payout on a multisig actor identified by its transaction Id
     #[command(arg_required_else_help = true)]
@@ -506,6 +1290,22 @@ pub enum Commands {
         #[arg(long, default_value = "local", value_enum)]
         method: SigningOptions,
     },
+    /// Replaces a message stuck in the mpool with one paying a higher premium
+    #[command(arg_required_else_help = true)]
+    BumpMessage {
+        /// CID of the stuck message.
+        #[arg(long, required_unless_present = "nonce", conflicts_with = "nonce")]
+        cid: Option<String>,
+        /// Nonce of the stuck message.
+        #[arg(long)]
+        nonce: Option<u64>,
+        /// Sender of the stuck message. Defaults to the signer address.
+        #[arg(long, requires = "nonce")]
+        from: Option<String>,
+        /// Signing Method for the command.
+        #[arg(long, default_value = "local", value_enum)]
+        method: SigningOptions,
+    },
     /// Cancels all proposed payouts on a multisig actor
     #[command(arg_required_else_help = true)]
     CancelAll {
@@ -528,6 +1328,14 @@ pub enum Commands {
         /// Signing Method for the command.
         #[arg(long, default_value = "local", value_enum)]
         method: SigningOptions,
+        /// Payout CSV the proposal was built from. The approval is refused
+        /// if the proposed payees, shares or total differ from it, or if
+        /// another pending proposal pays out the same batch.
+        #[arg(long, requires = "factory_address")]
+        expect_csv: Option<PathBuf>,
+        /// PayoutFactory Filecoin address the proposal has to be sent to.
+        #[arg(short = 'F', long, requires = "expect_csv")]
+        factory_address: Option<String>,
     },
     /// Approves all proposed payouts on a multisig actor
     #[command(arg_required_else_help = true)]
@@ -538,6 +1346,14 @@ pub enum Commands {
         /// Signing Method for the command.
         #[arg(long, default_value = "local", value_enum)]
         method: SigningOptions,
+        /// Payout CSV the proposals were built from. Nothing is approved if
+        /// any proposal does not match a batch of it, or if two proposals
+        /// pay out the same batch.
+        #[arg(long, requires = "factory_address")]
+        expect_csv: Option<PathBuf>,
+        /// PayoutFactory Filecoin address the proposals have to be sent to.
+        #[arg(short = 'F', long, requires = "expect_csv")]
+        factory_address: Option<String>,
     },
     /// Grants an admin role to a payout factory contract. The issuing address
     /// has to be an admin on the contract.
@@ -560,5 +1376,13 @@ pub enum Commands {
         /// PayoutFactory Ethereum address.
         #[arg(short = 'F', long)]
         factory_addr: String,
+        /// Block to replay the role events from when counting the admins,
+        /// e.g. the block the factory was deployed at.
+        #[arg(long)]
+        from_block: u64,
+        /// Revokes the role even from the last admin, which leaves the
+        /// factory unable to run payouts.
+        #[arg(long, default_value_t = false)]
+        i_understand_lockout: bool,
     },
 }
@@ -1,8 +1,9 @@
+use crate::error::CLIError;
 use crate::utils::format_date;
 use dotenv::dotenv;
-use rust_decimal::prelude::{Decimal, ToPrimitive};
+use rust_decimal::prelude::Decimal;
 use std::env;
-use tokio_postgres::{Client, Config, Error, NoTls, Row};
+use tokio_postgres::{Client, Config, NoTls, Row};
 /// Creates a new postgres database connection and returns a Postgres Client.
 ///
 /// Requires the following environment variables to be setup:
@@ -24,16 +25,15 @@ use tokio_postgres::{Client, Config, Error, NoTls, Row};
 ///
 /// ```
 ///
-async fn connect() -> Result<Client, Error> {
+async fn connect() -> Result<Client, CLIError> {
     dotenv().ok();
-    let pg_pass = env::var("PG_PASSWORD").expect("PG_PASSWORD must be set");
-    let pg_host = env::var("PG_HOST").expect("PG_HOST must be set");
-    let pg_db = env::var("PG_DATABASE").expect("PG_DATABASE must be set");
-    let pg_port: u16 = env::var("PG_PORT")
-        .expect("PG_PORT must be set")
+    let pg_pass = env_var("PG_PASSWORD")?;
+    let pg_host = env_var("PG_HOST")?;
+    let pg_db = env_var("PG_DATABASE")?;
+    let pg_port: u16 = env_var("PG_PORT")?
         .parse()
-        .unwrap();
-    let pg_user = env::var("PG_USER").expect("PG_USER must be set");
+        .map_err(|_| CLIError::Db(String::from("PG_PORT must be a port number")))?;
+    let pg_user = env_var("PG_USER")?;
 
     let (client, connection) = Config::new()
         .password(pg_pass)
@@ -43,8 +43,7 @@ async fn connect() -> Result<Client, Error> {
         .user(pg_user.as_str())
         .application_name("Saturn Contract Payments")
         .connect(NoTls)
-        .await
-        .unwrap();
+        .await?;
 
     tokio::spawn(async move {
         if let Err(e) = connection.await {
@@ -55,10 +54,14 @@ async fn connect() -> Result<Client, Error> {
     Ok(client)
 }
 
+fn env_var(name: &str) -> Result<String, CLIError> {
+    env::var(name).map_err(|_| CLIError::Db(format!("{} must be set", name)))
+}
+
 #[derive(Debug, Clone)]
 pub struct PayoutRecords {
     pub payees: Vec<String>,
-    pub shares: Vec<f64>,
+    pub shares: Vec<Decimal>,
 }
 
 /// Formats a vector tokio_postgres `Row` type to native rust types.
@@ -67,26 +70,26 @@ pub struct PayoutRecords {
 ///     - The first index is a postgres text/char type.
 ///     - The second row is a postgres numeric/int (any variant) / float type.
 ///
-fn format_payout_res(res: Vec<Row>) -> Result<PayoutRecords, Error> {
+fn format_payout_res(res: Vec<Row>) -> Result<PayoutRecords, CLIError> {
     let mut payees: Vec<String> = Vec::new();
-    let mut shares: Vec<f64> = Vec::new();
+    let mut shares: Vec<Decimal> = Vec::new();
 
     for row in res {
         let payee: String = row.get(0);
         let share: Decimal = row.get(1);
 
         payees.push(payee.to_string());
-        shares.push(share.to_f64().unwrap());
+        shares.push(share);
     }
     Ok(PayoutRecords { payees, shares })
 }
 
 /// Retrieves and aggregates payment information from the `payment_aggregation`
 /// table.
-pub async fn get_payment_records(date: &str) -> Result<PayoutRecords, Error> {
-    let client = connect().await.unwrap();
+pub async fn get_payment_records(date: &str) -> Result<PayoutRecords, CLIError> {
+    let client = connect().await?;
 
-    let date = format_date(date).unwrap();
+    let date = format_date(date)?;
 
     let res = client
         .query(
@@ -107,10 +110,7 @@ pub async fn get_payment_records(date: &str) -> Result<PayoutRecords, Error> {
     ",
             &[&date],
         )
-        .await
-        .unwrap();
-
-    let payout = format_payout_res(res).unwrap();
+        .await?;
 
-    Ok(payout)
+    format_payout_res(res)
 }
@@ -0,0 +1,212 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use contract_bindings::payment_splitter_native_addr::PaymentSplitterNativeAddr as PaymentSplitter;
+use contract_bindings::payout_factory_native_addr::PayoutFactoryNativeAddr as PayoutFactory;
+use contract_bindings::shared_types::FilAddress;
+use ethers::abi::Address;
+use ethers::providers::{Http, Provider};
+use ethers::types::U256;
+use fevm_utils::check_address_string;
+use log::info;
+use rust_decimal::Decimal;
+use tabled::Tabled;
+
+use crate::error::CLIError;
+use crate::index::{index_events, EventIndex, IndexedRelease};
+use crate::utils::{format_fil_address, format_table, format_u256};
+
+/// Earnings of an operator from a single payout contract.
+#[derive(Debug, Clone, PartialEq, Eq, Tabled)]
+pub struct SplitterEarnings {
+    #[tabled(rename = "Index")]
+    pub index: usize,
+    #[tabled(rename = "Payout Contract", display_with = "display_eth_address")]
+    pub contract: Address,
+    /// Month the payout contract was created in, if it was indexed.
+    #[tabled(rename = "Month", display_with = "display_month")]
+    pub month: Option<String>,
+    #[tabled(rename = "Share")]
+    pub share: Decimal,
+    #[tabled(rename = "Released")]
+    pub released: Decimal,
+    #[tabled(rename = "Releasable")]
+    pub releasable: Decimal,
+    #[tabled(rename = "Release Transactions", display_with = "display_tx_hashes")]
+    pub release_tx_hashes: Vec<String>,
+}
+
+fn display_eth_address(address: &Address) -> String {
+    format!("{:?}", address)
+}
+
+fn display_month(month: &Option<String>) -> String {
+    month.clone().unwrap_or_else(|| String::from("-"))
+}
+
+fn display_tx_hashes(hashes: &Vec<String>) -> String {
+    hashes.join("\n")
+}
+
+/// Year and month of a block timestamp, e.g. `2023-04`.
+pub fn payout_month(timestamp: u64) -> Option<String> {
+    Utc.timestamp_opt(timestamp as i64, 0)
+        .single()
+        .map(|date| date.format("%Y-%m").to_string())
+}
+
+/// Builds the earnings of an operator from the payout contract at `index`,
+/// keeping the indexed releases that were paid out by that contract.
+pub fn splitter_earnings(
+    index: usize,
+    contract: Address,
+    created_at: Option<u64>,
+    share: U256,
+    released: U256,
+    releasable: U256,
+    releases: &[IndexedRelease],
+) -> Result<SplitterEarnings, CLIError> {
+    let contract_address = display_eth_address(&contract);
+    Ok(SplitterEarnings {
+        index,
+        contract,
+        month: created_at.and_then(payout_month),
+        share: format_u256(share)?,
+        released: format_u256(released)?,
+        releasable: format_u256(releasable)?,
+        release_tx_hashes: releases
+            .iter()
+            .filter(|release| release.contract == contract_address)
+            .map(|release| release.tx_hash.clone())
+            .collect(),
+    })
+}
+
+/// Lists every payout contract of a factory `address` is a payee of, with
+/// its share, released and releasable amounts.
+///
+/// The payout months and release transactions are read from the event index
+/// at `db_path`, which is brought up to date first.
+pub async fn inspect_earnings_history(
+    provider: &Provider<Http>,
+    address: &str,
+    factory_address: &str,
+    db_path: &Path,
+    from_block: u64,
+) -> Result<Vec<SplitterEarnings>, CLIError> {
+    let factory_addr = Address::from_str(factory_address)?;
+    let client = Arc::new(provider.clone());
+    let factory = PayoutFactory::new(factory_addr, client.clone());
+
+    let payee = FilAddress {
+        data: check_address_string(address)
+            .map_err(CLIError::invalid_input)?
+            .bytes
+            .into(),
+    };
+
+    index_events(provider, factory_address, db_path, from_block).await?;
+    let index = EventIndex::open(db_path)?;
+    let releases = index.releases(&format_fil_address(&payee))?;
+
+    let (contracts, releasable, released) = factory
+        .releasable_per_contract(payee.clone())
+        .call()
+        .await?;
+
+    let mut history = Vec::new();
+    for (splitter_index, contract) in contracts.into_iter().enumerate() {
+        // contracts the address is not a payee of are left as the zero address
+        if contract.is_zero() {
+            continue;
+        }
+        let splitter = PaymentSplitter::new(contract, client.clone());
+        let share = splitter.shares(payee.clone()).call().await?;
+        let created_at = index
+            .splitter(&factory_addr, &contract)?
+            .map(|(_, timestamp)| timestamp);
+        if created_at.is_none() {
+            info!(
+                "payout contract {} ({:?}) is not indexed, index from the block the factory was deployed at",
+                splitter_index, contract
+            );
+        }
+        history.push(splitter_earnings(
+            splitter_index,
+            contract,
+            created_at,
+            share,
+            released[splitter_index],
+            releasable[splitter_index],
+            &releases,
+        )?);
+    }
+
+    let total_released: Decimal = history.iter().map(|earnings| earnings.released).sum();
+    let total_releasable: Decimal = history.iter().map(|earnings| earnings.releasable).sum();
+    info!(
+        "\n\n  Earnings history for address: {} \n\n{}\n\n  {} payout contracts, {} FIL released, {} FIL releasable",
+        address,
+        format_table(history.iter()),
+        history.len(),
+        total_released,
+        total_releasable
+    );
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{payout_month, splitter_earnings};
+    use crate::index::IndexedRelease;
+    use crate::utils::format_u256;
+    use ethers::abi::Address;
+    use ethers::types::U256;
+
+    #[test]
+    fn test_payout_month() {
+        // 2023-04-01T00:00:00Z
+        assert_eq!(payout_month(1_680_307_200), Some(String::from("2023-04")));
+        assert_eq!(payout_month(1_680_307_199), Some(String::from("2023-03")));
+    }
+
+    #[test]
+    fn test_splitter_earnings() {
+        let contract = Address::from_low_u64_be(2);
+        let other = Address::from_low_u64_be(3);
+        let release = |contract: Address, tx_hash: &str| IndexedRelease {
+            contract: format!("{:?}", contract),
+            amount: U256::from(1),
+            block: 10,
+            tx_hash: String::from(tx_hash),
+        };
+        let releases = vec![
+            release(contract, "0x01"),
+            release(other, "0x02"),
+            release(contract, "0x03"),
+        ];
+
+        let share = U256::exp10(18);
+        let earnings = splitter_earnings(
+            4,
+            contract,
+            Some(1_680_307_200),
+            share,
+            U256::exp10(17),
+            share - U256::exp10(17),
+            &releases,
+        )
+        .unwrap();
+        assert_eq!(earnings.index, 4);
+        assert_eq!(earnings.month, Some(String::from("2023-04")));
+        assert_eq!(earnings.share, format_u256(share).unwrap());
+        assert_eq!(earnings.released + earnings.releasable, earnings.share);
+        assert_eq!(earnings.release_tx_hashes, vec!["0x01", "0x03"]);
+
+        let earnings = splitter_earnings(4, contract, None, share, 0.into(), share, &[]).unwrap();
+        assert_eq!(earnings.month, None);
+        assert!(earnings.release_tx_hashes.is_empty());
+    }
+}
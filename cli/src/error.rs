@@ -0,0 +1,284 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use ethers::abi::Address;
+use ethers::contract::ContractError;
+use ethers::providers::{Middleware, MiddlewareError, ProviderError};
+use filecoin_signer::error::SignerError;
+use rust_decimal::Decimal;
+
+use crate::revert::{decode_revert_data, revert_reason};
+
+/// Errors returned by the cli.
+///
+/// Every variant belongs to a category, which `exit_code` maps to the exit
+/// code of the process such that scripts running the cli can tell failures
+/// apart.
+#[derive(thiserror::Error, Debug)]
+pub enum CLIError {
+    #[error("RPC request failed: {0}")]
+    Rpc(String),
+    #[error("signing failed: {0}")]
+    Signing(String),
+    #[error("ledger error: {0}")]
+    Ledger(String),
+    #[error("CSV error: {0}")]
+    Csv(String),
+    #[error("database error: {0}")]
+    Db(String),
+    #[error("contract call reverted: {0}")]
+    ContractRevert(String),
+    #[error("gas estimation failed: {0}")]
+    GasEstimation(String),
+    #[error("aborted: {0}")]
+    UserAbort(String),
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Other(String),
+
+    #[error("contract failed to deploy")]
+    ContractNotDeployed,
+    #[error("invalid FIL amount '{0}': {1}")]
+    InvalidFilAmount(String, String),
+    #[error("factory balance of {balance} FIL does not cover the {required} FIL left to pay out")]
+    InsufficientFactoryBalance { balance: Decimal, required: Decimal },
+    #[error("signer {0:?} does not hold the admin role on the factory")]
+    MissingAdminRole(Address),
+    #[error("the signing client has no sender address")]
+    MissingSender,
+    #[error("payout CSV has {0} invalid rows")]
+    InvalidPayoutCsv(usize),
+    #[error("message {0} failed with exit code {1}")]
+    MessageFailed(String, i64),
+    #[error("{0} is not pending in the mpool")]
+    MessageNotPending(String),
+    #[error("cannot roll back nonce {0}, the last allocated nonce is {1}")]
+    NonceRollback(u64, u64),
+    #[error("invalid gas policy: {0}")]
+    InvalidGasPolicy(String),
+    #[error("refusing to send: gas of {0} attoFIL is above the configured ceiling of {1}")]
+    GasAboveCeiling(String, String),
+    #[error("refusing to approve transaction {0}: {1}")]
+    ProposalMismatch(u64, String),
+    #[error("refusing to revoke {0:?}, the last admin of the factory; pass --i-understand-lockout to revoke it anyway")]
+    LastAdmin(Address),
+}
+
+impl CLIError {
+    /// Exit code of the process when a command fails with this error.
+    ///
+    /// | code | category                                 |
+    /// |------|------------------------------------------|
+    /// | 1    | other                                    |
+    /// | 3    | invalid input                            |
+    /// | 4    | CSV                                      |
+    /// | 5    | RPC                                      |
+    /// | 6    | signing                                  |
+    /// | 7    | Ledger                                   |
+    /// | 8    | database                                 |
+    /// | 9    | contract revert or failed message        |
+    /// | 10   | gas estimation or gas above the ceilings |
+    /// | 11   | aborted by the user                      |
+    ///
+    /// 2 is left to clap, which exits with it on invalid arguments.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CLIError::Other(_) | CLIError::Io(_) | CLIError::NonceRollback(..) => 1,
+            CLIError::InvalidInput(_)
+            | CLIError::InvalidFilAmount(..)
+            | CLIError::InvalidGasPolicy(_)
+            | CLIError::ProposalMismatch(..)
+            | CLIError::LastAdmin(_) => 3,
+            CLIError::Csv(_) | CLIError::InvalidPayoutCsv(_) => 4,
+            CLIError::Rpc(_) | CLIError::MessageNotPending(_) => 5,
+            CLIError::Signing(_) | CLIError::MissingSender => 6,
+            CLIError::Ledger(_) => 7,
+            CLIError::Db(_) => 8,
+            CLIError::ContractRevert(_)
+            | CLIError::ContractNotDeployed
+            | CLIError::InsufficientFactoryBalance { .. }
+            | CLIError::MissingAdminRole(_)
+            | CLIError::MessageFailed(..) => 9,
+            CLIError::GasEstimation(_) | CLIError::GasAboveCeiling(..) => 10,
+            CLIError::UserAbort(_) => 11,
+        }
+    }
+
+    pub fn rpc(error: impl Display) -> Self {
+        CLIError::Rpc(error.to_string())
+    }
+
+    pub fn signing(error: impl Display) -> Self {
+        CLIError::Signing(error.to_string())
+    }
+
+    /// Ledger errors, reported as `UserAbort` when the user rejected the
+    /// request on the device.
+    pub fn ledger(error: impl Display) -> Self {
+        let error = error.to_string();
+        if error.to_lowercase().contains("rejected") {
+            return CLIError::UserAbort(format!("rejected on the Ledger: {}", error));
+        }
+        CLIError::Ledger(error)
+    }
+
+    pub fn invalid_input(error: impl Display) -> Self {
+        CLIError::InvalidInput(error.to_string())
+    }
+
+    /// Errors of middleware requests, reported as `ContractRevert` with the
+    /// decoded reason when the node returned a revert, and as `category`
+    /// otherwise.
+    pub fn middleware<E: MiddlewareError>(error: E, category: fn(String) -> CLIError) -> Self {
+        match error.as_error_response().and_then(revert_reason) {
+            Some(reason) => CLIError::ContractRevert(reason),
+            None => category(error.to_string()),
+        }
+    }
+
+    /// Decoded revert reason of `ContractRevert` errors.
+    pub fn revert_reason(&self) -> Option<&str> {
+        match self {
+            CLIError::ContractRevert(reason) => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+impl From<Box<dyn Error>> for CLIError {
+    /// Keeps the category of boxed `CLIError`s, other errors are reported as
+    /// `Other`.
+    fn from(error: Box<dyn Error>) -> Self {
+        match error.downcast::<CLIError>() {
+            Ok(error) => *error,
+            Err(error) => CLIError::Other(error.to_string()),
+        }
+    }
+}
+
+impl From<String> for CLIError {
+    fn from(error: String) -> Self {
+        CLIError::Other(error)
+    }
+}
+
+impl From<&str> for CLIError {
+    fn from(error: &str) -> Self {
+        CLIError::Other(error.to_string())
+    }
+}
+
+impl From<ProviderError> for CLIError {
+    fn from(error: ProviderError) -> Self {
+        CLIError::middleware(error, CLIError::Rpc)
+    }
+}
+
+impl<M: Middleware> From<ContractError<M>> for CLIError {
+    fn from(error: ContractError<M>) -> Self {
+        match error {
+            ContractError::Revert(data) => CLIError::ContractRevert(decode_revert_data(&data)),
+            ContractError::MiddlewareError { e } => CLIError::middleware(e, CLIError::Rpc),
+            ContractError::ProviderError { e } => CLIError::middleware(e, CLIError::Rpc),
+            error => CLIError::Other(error.to_string()),
+        }
+    }
+}
+
+impl From<SignerError> for CLIError {
+    fn from(error: SignerError) -> Self {
+        CLIError::Signing(error.to_string())
+    }
+}
+
+impl From<csv::Error> for CLIError {
+    fn from(error: csv::Error) -> Self {
+        CLIError::Csv(error.to_string())
+    }
+}
+
+impl From<tokio_postgres::Error> for CLIError {
+    fn from(error: tokio_postgres::Error) -> Self {
+        CLIError::Db(error.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for CLIError {
+    fn from(error: rusqlite::Error) -> Self {
+        CLIError::Db(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for CLIError {
+    fn from(error: serde_json::Error) -> Self {
+        CLIError::Other(format!("JSON error: {}", error))
+    }
+}
+
+impl From<base64::DecodeError> for CLIError {
+    fn from(error: base64::DecodeError) -> Self {
+        CLIError::Other(format!("base64 error: {}", error))
+    }
+}
+
+impl From<fvm_shared::address::Error> for CLIError {
+    fn from(error: fvm_shared::address::Error) -> Self {
+        CLIError::InvalidInput(format!("invalid Filecoin address: {}", error))
+    }
+}
+
+impl From<fvm_shared::bigint::ParseBigIntError> for CLIError {
+    fn from(error: fvm_shared::bigint::ParseBigIntError) -> Self {
+        CLIError::InvalidInput(error.to_string())
+    }
+}
+
+impl From<hex::FromHexError> for CLIError {
+    fn from(error: hex::FromHexError) -> Self {
+        CLIError::InvalidInput(error.to_string())
+    }
+}
+
+impl From<std::num::ParseIntError> for CLIError {
+    fn from(error: std::num::ParseIntError) -> Self {
+        CLIError::InvalidInput(error.to_string())
+    }
+}
+
+impl From<<Address as FromStr>::Err> for CLIError {
+    fn from(error: <Address as FromStr>::Err) -> Self {
+        CLIError::InvalidInput(format!("invalid Ethereum address: {}", error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CLIError;
+    use std::error::Error;
+
+    #[test]
+    fn test_boxed_error_keeps_category() {
+        let boxed: Box<dyn Error> = CLIError::MessageFailed(String::from("bafy"), 33).into();
+        let error = CLIError::from(boxed);
+        assert_eq!(error.exit_code(), 9);
+
+        let boxed: Box<dyn Error> = "something else".into();
+        assert_eq!(CLIError::from(boxed).exit_code(), 1);
+    }
+
+    #[test]
+    fn test_ledger_rejection_is_user_abort() {
+        assert!(matches!(
+            CLIError::ledger("Transaction rejected"),
+            CLIError::UserAbort(_)
+        ));
+        assert!(matches!(
+            CLIError::ledger("device not found"),
+            CLIError::Ledger(_)
+        ));
+    }
+}
@@ -0,0 +1,307 @@
+use std::fs;
+use std::path::PathBuf;
+
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::U256;
+use fevm_utils::set_tx_gas;
+use fvm_shared::econ::TokenAmount;
+use log::info;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+use crate::error::CLIError;
+use crate::utils::TransactionGasInfo;
+
+/// Gas limit multiplier, in percent, applied to Filecoin message estimates
+/// when none is configured. Increase it to increase the chances of messages
+/// completing successfully. For context, 130 is typical.
+const DEFAULT_MESSAGE_GAS_LIMIT_MULTIPLIER: u64 = 600;
+/// Gas limit multiplier, in percent, applied to EVM transaction estimates when
+/// none is configured.
+const DEFAULT_TX_GAS_LIMIT_MULTIPLIER: u64 = 100;
+
+static GAS_POLICY: OnceCell<GasPolicy> = OnceCell::new();
+
+/// How the gas premium of Filecoin messages, and the priority fee of EVM
+/// transactions, is chosen.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PremiumStrategy {
+    /// Use the premium estimated by the node.
+    #[default]
+    Estimate,
+    /// Use the configured `premium`.
+    Fixed,
+}
+
+/// Gas settings applied to every message and transaction sent by the cli.
+///
+/// Amounts are in attoFIL, and per gas unit for the fee cap and premium.
+#[derive(clap::Args, Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "snake_case", deny_unknown_fields)]
+pub struct GasPolicy {
+    /// Gas limit multiplier in percent applied to Filecoin message estimates
+    /// [default: 600]
+    #[arg(long)]
+    pub message_gas_limit_multiplier: Option<u64>,
+    /// Gas limit multiplier in percent applied to EVM transaction estimates
+    /// [default: 100]
+    #[arg(long)]
+    pub tx_gas_limit_multiplier: Option<u64>,
+    /// Maximum fee in attoFIL a single message or transaction may cost. 0 lets
+    /// the node choose.
+    #[arg(long, default_value_t = 0)]
+    pub max_fee: u128,
+    /// Highest fee cap, or EVM gas price, in attoFIL per gas unit. Sending is
+    /// refused above it.
+    #[arg(long)]
+    pub fee_cap_ceiling: Option<u128>,
+    /// How the gas premium is chosen.
+    #[arg(long, value_enum, default_value_t = PremiumStrategy::Estimate)]
+    pub premium_strategy: PremiumStrategy,
+    /// Gas premium in attoFIL per gas unit for the `fixed` premium strategy.
+    #[arg(long)]
+    pub premium: Option<u128>,
+}
+
+impl GasPolicy {
+    /// Loads a policy from a JSON file. Missing fields take their default value.
+    pub fn load(path: &PathBuf) -> Result<Self, CLIError> {
+        serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|error| CLIError::InvalidGasPolicy(error.to_string()))
+    }
+
+    pub fn validate(&self) -> Result<(), CLIError> {
+        if self.premium_strategy == PremiumStrategy::Fixed && self.premium.is_none() {
+            return Err(CLIError::InvalidGasPolicy(String::from(
+                "the fixed premium strategy requires a premium",
+            )));
+        }
+        if let (Some(premium), Some(ceiling)) = (self.premium, self.fee_cap_ceiling) {
+            if premium > ceiling {
+                return Err(CLIError::InvalidGasPolicy(format!(
+                    "premium {} is above the fee cap ceiling {}",
+                    premium, ceiling
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// `MaxFee` message send spec passed to `Filecoin.GasEstimateMessageGas`.
+    pub fn max_fee_spec(&self) -> String {
+        self.max_fee.to_string()
+    }
+
+    /// Applies the policy to the gas estimated by the node for a Filecoin
+    /// message, failing if the result is above the configured ceilings.
+    pub fn apply_to_message(
+        &self,
+        estimate: TransactionGasInfo,
+    ) -> Result<TransactionGasInfo, CLIError> {
+        let multiplier = self
+            .message_gas_limit_multiplier
+            .unwrap_or(DEFAULT_MESSAGE_GAS_LIMIT_MULTIPLIER);
+        let mut gas_info = TransactionGasInfo {
+            gas_limit: estimate.gas_limit * multiplier / 100,
+            gas_fee_cap: estimate.gas_fee_cap,
+            gas_premium: estimate.gas_premium,
+        };
+        if let (PremiumStrategy::Fixed, Some(premium)) = (self.premium_strategy, self.premium) {
+            gas_info.gas_premium = TokenAmount::from_atto(premium);
+            if gas_info.gas_fee_cap < gas_info.gas_premium {
+                gas_info.gas_fee_cap = gas_info.gas_premium.clone();
+            }
+        }
+        self.check_message(&gas_info)?;
+        Ok(gas_info)
+    }
+
+    /// Fails if a Filecoin message paying `gas_info` is above the configured
+    /// ceilings.
+    pub fn check_message(&self, gas_info: &TransactionGasInfo) -> Result<(), CLIError> {
+        if let Some(ceiling) = self.fee_cap_ceiling {
+            if gas_info.gas_fee_cap > TokenAmount::from_atto(ceiling) {
+                return Err(CLIError::GasAboveCeiling(
+                    gas_info.gas_fee_cap.atto().to_string(),
+                    ceiling.to_string(),
+                ));
+            }
+        }
+        let fee = TokenAmount::from_atto(gas_info.gas_fee_cap.atto() * gas_info.gas_limit);
+        if self.max_fee > 0 && fee > TokenAmount::from_atto(self.max_fee) {
+            return Err(CLIError::GasAboveCeiling(
+                fee.atto().to_string(),
+                self.max_fee.to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Sets the gas of an EVM transaction from its estimate and the network gas
+    /// price, failing if the result is above the configured ceilings or if a
+    /// fixed premium is above the max fee per gas.
+    pub fn set_tx_gas(
+        &self,
+        tx: &mut TypedTransaction,
+        gas_estimate: U256,
+        gas_price: U256,
+    ) -> Result<(), CLIError> {
+        let multiplier = self
+            .tx_gas_limit_multiplier
+            .unwrap_or(DEFAULT_TX_GAS_LIMIT_MULTIPLIER);
+        let gas = gas_estimate * multiplier / 100;
+
+        if let Some(ceiling) = self.fee_cap_ceiling {
+            if gas_price > U256::from(ceiling) {
+                return Err(CLIError::GasAboveCeiling(
+                    gas_price.to_string(),
+                    ceiling.to_string(),
+                ));
+            }
+        }
+        if self.max_fee > 0 && gas.saturating_mul(gas_price) > U256::from(self.max_fee) {
+            return Err(CLIError::GasAboveCeiling(
+                gas.saturating_mul(gas_price).to_string(),
+                self.max_fee.to_string(),
+            ));
+        }
+
+        set_tx_gas(tx, gas, gas_price);
+        if let (PremiumStrategy::Fixed, Some(premium), TypedTransaction::Eip1559(inner)) =
+            (self.premium_strategy, self.premium, tx)
+        {
+            let premium = U256::from(premium);
+            let max_fee_per_gas = inner.max_fee_per_gas.unwrap_or(gas_price);
+            if premium > max_fee_per_gas {
+                return Err(CLIError::InvalidGasPolicy(format!(
+                    "premium {} is above the max fee per gas {}",
+                    premium, max_fee_per_gas
+                )));
+            }
+            inner.max_priority_fee_per_gas = Some(premium);
+        }
+        Ok(())
+    }
+}
+
+/// Sets the gas policy used by the cli. It can only be set once.
+pub fn set_gas_policy(policy: GasPolicy) -> Result<(), CLIError> {
+    policy.validate()?;
+    info!("using gas policy {:?}", policy);
+    GAS_POLICY
+        .set(policy)
+        .map_err(|_| CLIError::InvalidGasPolicy(String::from("gas policy already set")))
+}
+
+/// Returns the gas policy set with `set_gas_policy`, or the default policy.
+pub fn gas_policy() -> &'static GasPolicy {
+    GAS_POLICY.get_or_init(GasPolicy::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GasPolicy, PremiumStrategy};
+    use crate::utils::TransactionGasInfo;
+    use ethers::types::transaction::eip2718::TypedTransaction;
+    use ethers::types::{Eip1559TransactionRequest, U256};
+    use fvm_shared::econ::TokenAmount;
+
+    fn estimate() -> TransactionGasInfo {
+        TransactionGasInfo {
+            gas_limit: 1000,
+            gas_fee_cap: TokenAmount::from_atto(200),
+            gas_premium: TokenAmount::from_atto(100),
+        }
+    }
+
+    #[test]
+    fn test_default_gas_policy() {
+        let gas_info = GasPolicy::default().apply_to_message(estimate()).unwrap();
+        assert_eq!(gas_info.gas_limit, 6000);
+        assert_eq!(gas_info.gas_premium, TokenAmount::from_atto(100));
+    }
+
+    #[test]
+    fn test_gas_policy_ceilings() {
+        let policy = GasPolicy {
+            message_gas_limit_multiplier: Some(130),
+            tx_gas_limit_multiplier: Some(200),
+            premium_strategy: PremiumStrategy::Fixed,
+            premium: Some(300),
+            ..Default::default()
+        };
+        let gas_info = policy.apply_to_message(estimate()).unwrap();
+        assert_eq!(gas_info.gas_limit, 1300);
+        assert_eq!(gas_info.gas_fee_cap, TokenAmount::from_atto(300));
+
+        let capped = GasPolicy {
+            fee_cap_ceiling: Some(150),
+            ..Default::default()
+        };
+        assert!(capped.apply_to_message(estimate()).is_err());
+
+        let max_fee = GasPolicy {
+            max_fee: 6000 * 200 - 1,
+            ..Default::default()
+        };
+        assert!(max_fee.apply_to_message(estimate()).is_err());
+    }
+
+    #[test]
+    fn test_tx_gas_policy() {
+        let policy = GasPolicy {
+            tx_gas_limit_multiplier: Some(150),
+            premium_strategy: PremiumStrategy::Fixed,
+            premium: Some(300),
+            ..Default::default()
+        };
+        let mut tx = TypedTransaction::Eip1559(Eip1559TransactionRequest::new());
+        policy
+            .set_tx_gas(&mut tx, U256::from(1000), U256::from(400))
+            .unwrap();
+        assert_eq!(tx.gas(), Some(&U256::from(1500)));
+        match tx {
+            TypedTransaction::Eip1559(inner) => {
+                assert_eq!(inner.max_priority_fee_per_gas, Some(U256::from(300)))
+            }
+            _ => panic!("expected an EIP-1559 transaction"),
+        }
+
+        // the message multiplier does not apply to transactions
+        let policy = GasPolicy {
+            message_gas_limit_multiplier: Some(130),
+            ..Default::default()
+        };
+        let mut tx = TypedTransaction::Eip1559(Eip1559TransactionRequest::new());
+        policy
+            .set_tx_gas(&mut tx, U256::from(1000), U256::from(400))
+            .unwrap();
+        assert_eq!(tx.gas(), Some(&U256::from(1000)));
+    }
+
+    #[test]
+    fn test_premium_above_max_fee_per_gas() {
+        let policy = GasPolicy {
+            premium_strategy: PremiumStrategy::Fixed,
+            premium: Some(300),
+            ..Default::default()
+        };
+        let mut tx = TypedTransaction::Eip1559(Eip1559TransactionRequest::new());
+        assert!(policy
+            .set_tx_gas(&mut tx, U256::from(1000), U256::from(200))
+            .is_err());
+    }
+
+    #[test]
+    fn test_load_gas_policy() {
+        let policy: GasPolicy =
+            serde_json::from_str(r#"{"fee_cap_ceiling": 1000, "premium_strategy": "fixed"}"#)
+                .unwrap();
+        assert_eq!(policy.fee_cap_ceiling, Some(1000));
+        assert!(policy.validate().is_err());
+
+        // the multiplier is set per kind of message
+        assert!(serde_json::from_str::<GasPolicy>(r#"{"gas_limit_multiplier": 130}"#).is_err());
+    }
+}
@@ -0,0 +1,482 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use contract_bindings::payment_splitter_native_addr::PaymentSplitterNativeAddr as PaymentSplitter;
+use contract_bindings::payout_factory_native_addr::PayoutFactoryNativeAddr as PayoutFactory;
+use contract_bindings::shared_types::FilAddress;
+use ethers::abi::Address;
+use ethers::contract::LogMeta;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::{ValueOrArray, U256};
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::error::CLIError;
+use crate::utils::{format_fil_address, LOG_QUERY_RANGE};
+
+/// Number of blocks the head of the chain is trailed by, so that indexed
+/// events are not reverted by a reorg.
+const INDEX_CONFIDENCE: u64 = 5;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS cursors (
+    factory TEXT PRIMARY KEY,
+    next_block INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS splitters (
+    address TEXT PRIMARY KEY,
+    factory TEXT NOT NULL,
+    splitter_index INTEGER NOT NULL,
+    block INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS payees (
+    splitter TEXT NOT NULL,
+    payee TEXT NOT NULL,
+    shares TEXT NOT NULL,
+    block INTEGER NOT NULL,
+    log_index INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL,
+    PRIMARY KEY (block, log_index)
+);
+CREATE TABLE IF NOT EXISTS releases (
+    contract TEXT NOT NULL,
+    payee TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    block INTEGER NOT NULL,
+    log_index INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL,
+    PRIMARY KEY (block, log_index)
+);
+CREATE TABLE IF NOT EXISTS factory_releases (
+    factory TEXT NOT NULL,
+    payee TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    block INTEGER NOT NULL,
+    log_index INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL,
+    PRIMARY KEY (block, log_index)
+);
+CREATE TABLE IF NOT EXISTS receipts (
+    contract TEXT NOT NULL,
+    sender TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    block INTEGER NOT NULL,
+    log_index INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL,
+    PRIMARY KEY (block, log_index)
+);
+CREATE INDEX IF NOT EXISTS payees_by_payee ON payees (payee);
+CREATE INDEX IF NOT EXISTS releases_by_payee ON releases (payee);
+";
+
+/// Events of a range of blocks, recorded together with the cursor.
+#[derive(Debug, Clone, Default)]
+pub struct IndexedEvents {
+    /// Splitters created by the factory, with their index in `payouts` and
+    /// the timestamp of their block.
+    pub splitters: Vec<(Address, u64, u64, LogMeta)>,
+    /// `PayeeAdded` events of the splitters.
+    pub payees: Vec<(FilAddress, U256, LogMeta)>,
+    /// `PaymentReleased` events of the splitters.
+    pub releases: Vec<(FilAddress, U256, LogMeta)>,
+    /// `PaymentReleased` events of the factory, kept apart from the releases
+    /// of the splitters they also show up as.
+    pub factory_releases: Vec<(FilAddress, U256, LogMeta)>,
+    /// `PaymentReceived` events of the factory.
+    pub receipts: Vec<(Address, U256, LogMeta)>,
+}
+
+/// A release of funds to a payee, as indexed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedRelease {
+    pub contract: String,
+    pub amount: U256,
+    pub block: u64,
+    pub tx_hash: String,
+}
+
+/// SQLite file the events of a factory and its splitters are indexed in.
+pub struct EventIndex {
+    connection: Connection,
+}
+
+fn format_eth_address(address: &Address) -> String {
+    format!("{:?}", address)
+}
+
+impl EventIndex {
+    /// Opens the index at `path`, creating it if it does not exist.
+    pub fn open(path: &Path) -> Result<Self, CLIError> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(EventIndex { connection })
+    }
+
+    /// First block left to index for `factory`, if it was indexed before.
+    pub fn cursor(&self, factory: &Address) -> Result<Option<u64>, CLIError> {
+        let next_block: Option<i64> = self
+            .connection
+            .query_row(
+                "SELECT next_block FROM cursors WHERE factory = ?1",
+                params![format_eth_address(factory)],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(next_block.map(|block| block as u64))
+    }
+
+    /// Splitters of `factory` indexed so far, in creation order.
+    pub fn splitters(&self, factory: &Address) -> Result<Vec<Address>, CLIError> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT address FROM splitters WHERE factory = ?1 ORDER BY splitter_index")?;
+        let addresses = statement
+            .query_map(params![format_eth_address(factory)], |row| {
+                row.get::<_, String>(0)
+            })?
+            .collect::<Result<Vec<String>, _>>()?;
+        addresses
+            .iter()
+            .map(|address| Ok(Address::from_str(address)?))
+            .collect()
+    }
+
+    /// Index and creation timestamp of a splitter of `factory`.
+    pub fn splitter(
+        &self,
+        factory: &Address,
+        splitter: &Address,
+    ) -> Result<Option<(u64, u64)>, CLIError> {
+        let splitter: Option<(i64, i64)> = self
+            .connection
+            .query_row(
+                "SELECT splitter_index, timestamp FROM splitters WHERE factory = ?1 AND address = ?2",
+                params![format_eth_address(factory), format_eth_address(splitter)],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        Ok(splitter.map(|(index, timestamp)| (index as u64, timestamp as u64)))
+    }
+
+    /// Releases of funds to `payee`, a Filecoin address, in chain order.
+    pub fn releases(&self, payee: &str) -> Result<Vec<IndexedRelease>, CLIError> {
+        let mut statement = self.connection.prepare(
+            "SELECT contract, amount, block, tx_hash FROM releases WHERE payee = ?1 ORDER BY block, log_index",
+        )?;
+        let rows = statement
+            .query_map(params![payee], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows.into_iter()
+            .map(|(contract, amount, block, tx_hash)| {
+                Ok(IndexedRelease {
+                    contract,
+                    amount: U256::from_dec_str(&amount)
+                        .map_err(|error| CLIError::Db(error.to_string()))?,
+                    block: block as u64,
+                    tx_hash,
+                })
+            })
+            .collect()
+    }
+
+    /// Records the events of a range of blocks and moves the cursor of
+    /// `factory` to `next_block`, in a single transaction such that an
+    /// interrupted run resumes from the last recorded range.
+    pub fn record(
+        &mut self,
+        factory: &Address,
+        events: &IndexedEvents,
+        next_block: u64,
+    ) -> Result<(), CLIError> {
+        let factory = format_eth_address(factory);
+        let transaction = self.connection.transaction()?;
+        for (splitter, index, timestamp, meta) in events.splitters.iter() {
+            transaction.execute(
+                "INSERT OR IGNORE INTO splitters (address, factory, splitter_index, block, timestamp, tx_hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    format_eth_address(splitter),
+                    factory,
+                    *index as i64,
+                    meta.block_number.as_u64() as i64,
+                    *timestamp as i64,
+                    format!("{:?}", meta.transaction_hash),
+                ],
+            )?;
+        }
+        for (payee, shares, meta) in events.payees.iter() {
+            transaction.execute(
+                "INSERT OR IGNORE INTO payees (splitter, payee, shares, block, log_index, tx_hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    format_eth_address(&meta.address),
+                    format_fil_address(payee),
+                    shares.to_string(),
+                    meta.block_number.as_u64() as i64,
+                    meta.log_index.as_u64() as i64,
+                    format!("{:?}", meta.transaction_hash),
+                ],
+            )?;
+        }
+        for (payee, amount, meta) in events.releases.iter() {
+            transaction.execute(
+                "INSERT OR IGNORE INTO releases (contract, payee, amount, block, log_index, tx_hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    format_eth_address(&meta.address),
+                    format_fil_address(payee),
+                    amount.to_string(),
+                    meta.block_number.as_u64() as i64,
+                    meta.log_index.as_u64() as i64,
+                    format!("{:?}", meta.transaction_hash),
+                ],
+            )?;
+        }
+        for (payee, amount, meta) in events.factory_releases.iter() {
+            transaction.execute(
+                "INSERT OR IGNORE INTO factory_releases (factory, payee, amount, block, log_index, tx_hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    factory,
+                    format_fil_address(payee),
+                    amount.to_string(),
+                    meta.block_number.as_u64() as i64,
+                    meta.log_index.as_u64() as i64,
+                    format!("{:?}", meta.transaction_hash),
+                ],
+            )?;
+        }
+        for (sender, amount, meta) in events.receipts.iter() {
+            transaction.execute(
+                "INSERT OR IGNORE INTO receipts (contract, sender, amount, block, log_index, tx_hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    format_eth_address(&meta.address),
+                    format_eth_address(sender),
+                    amount.to_string(),
+                    meta.block_number.as_u64() as i64,
+                    meta.log_index.as_u64() as i64,
+                    format!("{:?}", meta.transaction_hash),
+                ],
+            )?;
+        }
+        transaction.execute(
+            "INSERT INTO cursors (factory, next_block) VALUES (?1, ?2)
+             ON CONFLICT (factory) DO UPDATE SET next_block = excluded.next_block",
+            params![factory, next_block as i64],
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+/// Fetches the events of the factory and of its `splitters` between `start`
+/// and `end`. Splitters created in the range are queried along with the
+/// others, so the events they emit on creation are in the same range.
+async fn fetch_events(
+    provider: &Provider<Http>,
+    factory: &PayoutFactory<Provider<Http>>,
+    mut splitters: Vec<Address>,
+    start: u64,
+    end: u64,
+) -> Result<IndexedEvents, CLIError> {
+    let mut events = IndexedEvents::default();
+
+    let created = factory
+        .splitter_created_filter()
+        .from_block(start)
+        .to_block(end)
+        .query_with_meta()
+        .await?;
+    // the index of a splitter is its position in `payouts`, which only grows
+    let payouts = if created.is_empty() {
+        Vec::new()
+    } else {
+        factory.payouts().call().await?
+    };
+    for (event, meta) in created {
+        let index = payouts
+            .iter()
+            .position(|payout| *payout == event.new_splitter)
+            .ok_or_else(|| {
+                CLIError::Rpc(format!(
+                    "splitter {:?} is not in the payouts of the factory",
+                    event.new_splitter
+                ))
+            })?;
+        let timestamp = provider
+            .get_block(meta.block_number)
+            .await?
+            .map(|block| block.timestamp.as_u64())
+            .ok_or_else(|| CLIError::Rpc(format!("block {} not found", meta.block_number)))?;
+        splitters.push(event.new_splitter);
+        events
+            .splitters
+            .push((event.new_splitter, index as u64, timestamp, meta));
+    }
+
+    let released = factory
+        .payment_released_filter()
+        .from_block(start)
+        .to_block(end)
+        .query_with_meta()
+        .await?;
+    events.factory_releases.extend(
+        released
+            .into_iter()
+            .map(|(event, meta)| (event.to, event.amount, meta)),
+    );
+    let received = factory
+        .payment_received_filter()
+        .from_block(start)
+        .to_block(end)
+        .query_with_meta()
+        .await?;
+    events.receipts.extend(
+        received
+            .into_iter()
+            .map(|(event, meta)| (event.from, event.amount, meta)),
+    );
+
+    if let Some(first) = splitters.first() {
+        let splitter = PaymentSplitter::new(*first, Arc::new(provider.clone()));
+        let payees = splitter
+            .payee_added_filter()
+            .address(ValueOrArray::Array(splitters.clone()))
+            .from_block(start)
+            .to_block(end)
+            .query_with_meta()
+            .await?;
+        events.payees.extend(
+            payees
+                .into_iter()
+                .map(|(event, meta)| (event.account, event.shares, meta)),
+        );
+        let released = splitter
+            .payment_released_filter()
+            .address(ValueOrArray::Array(splitters))
+            .from_block(start)
+            .to_block(end)
+            .query_with_meta()
+            .await?;
+        events.releases.extend(
+            released
+                .into_iter()
+                .map(|(event, meta)| (event.to, event.amount, meta)),
+        );
+    }
+    Ok(events)
+}
+
+/// Indexes the events of a factory and of its splitters into the SQLite file
+/// at `db_path`, from the cursor of a previous run or from `from_block`.
+pub async fn index_events(
+    provider: &Provider<Http>,
+    factory_addr: &str,
+    db_path: &Path,
+    from_block: u64,
+) -> Result<(), CLIError> {
+    let factory_address = Address::from_str(factory_addr)?;
+    let factory = PayoutFactory::new(factory_address, Arc::new(provider.clone()));
+    let mut index = EventIndex::open(db_path)?;
+
+    let start = index.cursor(&factory_address)?.unwrap_or(from_block);
+    let latest = provider
+        .get_block_number()
+        .await?
+        .as_u64()
+        .saturating_sub(INDEX_CONFIDENCE);
+    if start > latest {
+        info!("index is up to date at block {}", latest);
+        return Ok(());
+    }
+    info!("indexing blocks {} to {}", start, latest);
+
+    for start in (start..=latest).step_by(LOG_QUERY_RANGE as usize) {
+        let end = (start + LOG_QUERY_RANGE - 1).min(latest);
+        let splitters = index.splitters(&factory_address)?;
+        let events = fetch_events(provider, &factory, splitters, start, end).await?;
+        index.record(&factory_address, &events, end + 1)?;
+        info!(
+            "indexed blocks {} to {}: {} splitters, {} payees, {} releases, {} factory releases, {} receipts",
+            start,
+            end,
+            events.splitters.len(),
+            events.payees.len(),
+            events.releases.len(),
+            events.factory_releases.len(),
+            events.receipts.len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventIndex, IndexedEvents};
+    use crate::utils::format_fil_address;
+    use contract_bindings::shared_types::FilAddress;
+    use ethers::abi::Address;
+    use ethers::contract::LogMeta;
+    use ethers::types::{U256, U64};
+    use fvm_shared::address::Address as FilecoinAddress;
+
+    fn meta(contract: Address, block: u64, index: u64) -> LogMeta {
+        LogMeta {
+            address: contract,
+            block_number: U64::from(block),
+            block_hash: Default::default(),
+            transaction_hash: Default::default(),
+            transaction_index: U64::zero(),
+            log_index: U256::from(index),
+        }
+    }
+
+    #[test]
+    fn test_record_events() {
+        let db = assert_fs::NamedTempFile::new("events.sqlite").unwrap();
+        let factory = Address::from_low_u64_be(1);
+        let splitter = Address::from_low_u64_be(2);
+        let payee = FilAddress {
+            data: FilecoinAddress::new_id(100).to_bytes().into(),
+        };
+        let events = IndexedEvents {
+            // indexing started after the first splitter of the factory
+            splitters: vec![(splitter, 1, 1_680_000_000, meta(factory, 10, 0))],
+            payees: vec![(payee.clone(), U256::from(5), meta(splitter, 10, 1))],
+            releases: vec![(payee.clone(), U256::from(3), meta(splitter, 20, 1))],
+            factory_releases: vec![(payee.clone(), U256::from(3), meta(factory, 20, 0))],
+            receipts: vec![],
+        };
+
+        let mut index = EventIndex::open(db.path()).unwrap();
+        assert_eq!(index.cursor(&factory).unwrap(), None);
+        index.record(&factory, &events, 21).unwrap();
+        // recording a range again does not duplicate its events
+        index.record(&factory, &events, 21).unwrap();
+
+        let index = EventIndex::open(db.path()).unwrap();
+        assert_eq!(index.cursor(&factory).unwrap(), Some(21));
+        assert_eq!(index.splitters(&factory).unwrap(), vec![splitter]);
+        assert_eq!(
+            index.splitter(&factory, &splitter).unwrap(),
+            Some((1, 1_680_000_000))
+        );
+        let releases = index.releases(&format_fil_address(&payee)).unwrap();
+        // the release of the factory is not counted as a second release
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].contract, format!("{:?}", splitter));
+        assert_eq!(releases[0].amount, U256::from(3));
+        assert_eq!(releases[0].block, 20);
+    }
+}
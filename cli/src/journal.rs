@@ -0,0 +1,270 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use contract_bindings::shared_types::FilAddress;
+use ethers::types::{H256, U256};
+use ethers::utils::keccak256;
+use serde::{Deserialize, Serialize};
+
+use crate::error::CLIError;
+use crate::utils::batch_ranges;
+
+/// Lifecycle of a single payout batch within a journaled run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BatchStatus {
+    /// The batch has not been sent yet.
+    Pending,
+    /// The batch transaction was broadcast but its receipt was not confirmed yet.
+    Submitted,
+    /// The batch transaction was mined successfully.
+    Confirmed,
+    /// The batch failed before or during submission.
+    Failed,
+}
+
+/// A journal entry for a single `payout` call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchRecord {
+    pub start_index: usize,
+    pub end_index: usize,
+    /// Hash over the payees and shares of the batch, used to detect payout data
+    /// that changed between a run and its resumption.
+    pub payee_hash: String,
+    pub tx_hash: Option<H256>,
+    pub status: BatchStatus,
+}
+
+/// On-disk record of a payout run.
+///
+/// Every batch deployed by `new-payout` is written to the journal before and
+/// after it is sent, such that a failed run can be resumed without paying any
+/// batch twice.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PayoutJournal {
+    #[serde(skip)]
+    path: PathBuf,
+    pub factory_address: String,
+    pub created_at: String,
+    pub payee_count: usize,
+    pub batches: Vec<BatchRecord>,
+}
+
+impl PayoutJournal {
+    /// Creates a journal with a `Pending` entry per batch and writes it to `path`.
+    pub fn create(
+        path: &Path,
+        factory_address: &str,
+        payees: &[FilAddress],
+        shares: &[U256],
+        batch_size: usize,
+    ) -> Result<Self, CLIError> {
+        assert_eq!(payees.len(), shares.len());
+        let batches = batch_ranges(payees.len(), batch_size)
+            .into_iter()
+            .map(|(start_index, end_index)| BatchRecord {
+                start_index,
+                end_index,
+                payee_hash: batch_hash(
+                    &payees[start_index..end_index],
+                    &shares[start_index..end_index],
+                ),
+                tx_hash: None,
+                status: BatchStatus::Pending,
+            })
+            .collect();
+
+        let journal = PayoutJournal {
+            path: path.to_path_buf(),
+            factory_address: factory_address.to_string(),
+            created_at: chrono::offset::Utc::now().to_string(),
+            payee_count: payees.len(),
+            batches,
+        };
+        journal.save()?;
+        Ok(journal)
+    }
+
+    /// Loads a journal previously written by [`PayoutJournal::create`].
+    pub fn load(path: &Path) -> Result<Self, CLIError> {
+        let contents = fs::read_to_string(path)?;
+        let mut journal: PayoutJournal = serde_json::from_str(&contents)?;
+        journal.path = path.to_path_buf();
+        Ok(journal)
+    }
+
+    /// Persists the journal. The file is replaced atomically so that an
+    /// interrupted write never leaves a truncated journal behind.
+    pub fn save(&self) -> Result<(), CLIError> {
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Checks that the journal describes the same payout data as `payees` and
+    /// `shares`. Resuming against different data would deploy the wrong batches.
+    pub fn verify(
+        &self,
+        factory_address: &str,
+        payees: &[FilAddress],
+        shares: &[U256],
+    ) -> Result<(), CLIError> {
+        if !self.factory_address.eq_ignore_ascii_case(factory_address) {
+            return Err(CLIError::InvalidInput(format!(
+                "journal was written for factory {} but resuming against {}",
+                self.factory_address, factory_address
+            )));
+        }
+        if self.payee_count != payees.len() || payees.len() != shares.len() {
+            return Err(CLIError::InvalidInput(format!(
+                "journal covers {} payees but the payout data has {}",
+                self.payee_count,
+                payees.len()
+            )));
+        }
+        // the batches must cover the payees in order, a hand-edited journal
+        // could otherwise skip payees or index past them
+        let mut next_index = 0;
+        for batch in self.batches.iter() {
+            if batch.start_index != next_index
+                || batch.end_index < batch.start_index
+                || batch.end_index > payees.len()
+            {
+                return Err(CLIError::InvalidInput(format!(
+                    "journal batch {} .. {} does not follow on from index {} within {} payees",
+                    batch.start_index,
+                    batch.end_index,
+                    next_index,
+                    payees.len()
+                )));
+            }
+            next_index = batch.end_index;
+        }
+        if next_index != payees.len() {
+            return Err(CLIError::InvalidInput(format!(
+                "journal batches cover {} of {} payees",
+                next_index,
+                payees.len()
+            )));
+        }
+        for batch in self.batches.iter() {
+            let hash = batch_hash(
+                &payees[batch.start_index..batch.end_index],
+                &shares[batch.start_index..batch.end_index],
+            );
+            if hash != batch.payee_hash {
+                return Err(CLIError::InvalidInput(format!(
+                    "payout data for index range {} .. {} does not match the journal",
+                    batch.start_index, batch.end_index
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the status of batch `index` and persists the journal.
+    pub fn record(
+        &mut self,
+        index: usize,
+        status: BatchStatus,
+        tx_hash: Option<H256>,
+    ) -> Result<(), CLIError> {
+        let batch = &mut self.batches[index];
+        batch.status = status;
+        if tx_hash.is_some() {
+            batch.tx_hash = tx_hash;
+        }
+        self.save()
+    }
+}
+
+/// Hashes a batch of payees and their shares, as they would be sent on chain.
+pub fn batch_hash(payees: &[FilAddress], shares: &[U256]) -> String {
+    let mut data: Vec<u8> = Vec::new();
+    for (payee, share) in payees.iter().zip(shares.iter()) {
+        data.push(payee.data.len() as u8);
+        data.extend_from_slice(&payee.data);
+        let mut share_bytes = [0u8; 32];
+        share.to_big_endian(&mut share_bytes);
+        data.extend_from_slice(&share_bytes);
+    }
+    format!("0x{}", hex::encode(keccak256(data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchStatus, PayoutJournal};
+    use contract_bindings::shared_types::FilAddress;
+    use ethers::types::{H256, U256};
+
+    fn payout_data(size: usize) -> (Vec<FilAddress>, Vec<U256>) {
+        let payees = (0..size)
+            .map(|i| FilAddress {
+                data: vec![0, i as u8].into(),
+            })
+            .collect();
+        let shares = (0..size).map(|i| U256::from(i + 1)).collect();
+        (payees, shares)
+    }
+
+    #[test]
+    fn test_journal_batches_and_roundtrip() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("journal.json");
+        let (payees, shares) = payout_data(7);
+
+        let mut journal = PayoutJournal::create(&path, "0xabc", &payees, &shares, 3).unwrap();
+        let ranges: Vec<(usize, usize)> = journal
+            .batches
+            .iter()
+            .map(|batch| (batch.start_index, batch.end_index))
+            .collect();
+        assert_eq!(ranges, vec![(0, 3), (3, 6), (6, 7)]);
+
+        journal
+            .record(1, BatchStatus::Submitted, Some(H256::repeat_byte(1)))
+            .unwrap();
+
+        let loaded = PayoutJournal::load(&path).unwrap();
+        assert_eq!(loaded, journal);
+        assert_eq!(loaded.batches[1].status, BatchStatus::Submitted);
+        assert!(loaded.verify("0xABC", &payees, &shares).is_ok());
+    }
+
+    #[test]
+    fn test_journal_rejects_changed_payouts() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("journal.json");
+        let (payees, mut shares) = payout_data(5);
+
+        let journal = PayoutJournal::create(&path, "0xabc", &payees, &shares, 2).unwrap();
+        shares[4] = U256::from(100);
+
+        assert!(journal.verify("0xabc", &payees, &shares).is_err());
+        assert!(journal.verify("0xdef", &payees, &payout_data(5).1).is_err());
+    }
+
+    #[test]
+    fn test_journal_rejects_invalid_batches() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("journal.json");
+        let (payees, shares) = payout_data(5);
+        let journal = PayoutJournal::create(&path, "0xabc", &payees, &shares, 2).unwrap();
+
+        let mut out_of_range = journal.clone();
+        out_of_range.batches[2].end_index = 9;
+        assert!(out_of_range.verify("0xabc", &payees, &shares).is_err());
+
+        let mut gap = journal.clone();
+        gap.batches[1].start_index = 3;
+        assert!(gap.verify("0xabc", &payees, &shares).is_err());
+
+        let mut truncated = journal;
+        truncated.batches.pop();
+        assert!(truncated.verify("0xabc", &payees, &shares).is_err());
+    }
+}
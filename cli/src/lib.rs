@@ -1,11 +1,56 @@
 //! A library for deploying saturn contracts
 //!
 
+/// Audit of the admins of a payout factory
+pub mod admins;
+
+/// Bulk release of earnings on behalf of node operators
+pub mod claim;
+
 /// CLI commands.
 pub mod commands;
 
 /// Database Utils
 pub mod db;
 
+/// Per payout contract history of an operator's earnings
+pub mod earnings;
+
+/// Errors returned by the cli
+pub mod error;
+
+/// Gas policy for Filecoin messages and EVM transactions
+pub mod gas;
+
+/// Local SQLite index of factory and splitter events
+pub mod index;
+
+/// Payout run journal
+pub mod journal;
+
+/// Payout deployment plans
+pub mod plan;
+
+/// Replacement of stuck mpool messages
+pub mod mpool;
+
+/// Decoding of pending multisig transactions
+pub mod multisig;
+
+/// Filecoin message receipts
+pub mod receipt;
+
+/// Month-end report of the funds a factory owes to payees
+pub mod report;
+
+/// Decoding of contract revert reasons
+pub mod revert;
+
 /// General cli Utils
 pub mod utils;
+
+/// Validation of payout CSVs
+pub mod validate;
+
+/// Reconciliation of payout CSVs against deployed payouts
+pub mod verify;
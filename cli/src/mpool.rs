@@ -0,0 +1,214 @@
+use std::str::FromStr;
+
+use ethers::providers::{Http, Provider};
+use filecoin_signer::api::MessageTxAPI;
+use fvm_shared::address::Address as FilecoinAddress;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::message::Message;
+use log::info;
+use serde_json::Value;
+
+use crate::error::CLIError;
+use crate::gas::gas_policy;
+use crate::receipt::search_message;
+use crate::utils::{
+    get_gas_info, push_mpool_message, sign_message_with_gas, SignatureMethod, TransactionGasInfo,
+};
+
+/// Premium, in percent of the pending premium, applied to a replacement
+/// message. Lotus rejects replacements below 110% and uses 125% by default
+/// for `mpool replace --auto`, which is followed here to keep a margin above
+/// the minimum.
+const REPLACE_BY_FEE_PERCENTAGE: u64 = 125;
+
+/// Identifies a message waiting in the mpool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PendingMessageId {
+    Cid(String),
+    Nonce { sender: FilecoinAddress, nonce: u64 },
+}
+
+impl std::fmt::Display for PendingMessageId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PendingMessageId::Cid(cid) => write!(f, "message {}", cid),
+            PendingMessageId::Nonce { sender, nonce } => {
+                write!(f, "message from {} with nonce {}", sender, nonce)
+            }
+        }
+    }
+}
+
+/// Hands out consecutive nonces for a sender.
+///
+/// The first nonce is read once from `Filecoin.MpoolGetNonce`, which accounts
+/// for the sender's pending messages, and later ones are incremented locally
+/// such that messages sent in a loop do not replace each other while they are
+/// still pending.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonceAllocator {
+    address: String,
+    next: u64,
+}
+
+impl NonceAllocator {
+    /// Creates an allocator starting at the mpool nonce of `address`.
+    pub async fn new(provider: &Provider<Http>, address: &str) -> Result<Self, CLIError> {
+        let nonce: u64 = provider
+            .request::<[&str; 1], u64>("Filecoin.MpoolGetNonce", [address])
+            .await?;
+        Ok(Self::starting_at(address, nonce))
+    }
+
+    /// Creates an allocator whose first nonce is `nonce`.
+    pub fn starting_at(address: &str, nonce: u64) -> Self {
+        NonceAllocator {
+            address: address.to_string(),
+            next: nonce,
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Allocates the next nonce.
+    pub fn next(&mut self) -> u64 {
+        let nonce = self.next;
+        self.next += 1;
+        nonce
+    }
+
+    /// Returns a nonce whose message could not be pushed, such that it is
+    /// allocated again. Only the last allocated nonce can be returned, as any
+    /// later nonce would otherwise leave a gap.
+    pub fn rollback(&mut self, nonce: u64) -> Result<(), CLIError> {
+        if nonce + 1 != self.next {
+            return Err(CLIError::NonceRollback(nonce, self.next - 1));
+        }
+        self.next = nonce;
+        Ok(())
+    }
+}
+
+/// Returns the lowest premium the mpool accepts to replace a message paying
+/// `premium`.
+///
+/// ```
+/// # use cli::mpool::min_replacement_premium;
+/// # use fvm_shared::econ::TokenAmount;
+/// assert_eq!(
+///     min_replacement_premium(&TokenAmount::from_atto(100)),
+///     TokenAmount::from_atto(126)
+/// );
+/// ```
+pub fn min_replacement_premium(premium: &TokenAmount) -> TokenAmount {
+    TokenAmount::from_atto(premium.atto() * REPLACE_BY_FEE_PERCENTAGE / 100u64 + 1u64)
+}
+
+/// Finds a message in the mpool of the node, returning `None` if it is not
+/// pending.
+pub async fn find_pending_message(
+    provider: &Provider<Http>,
+    id: &PendingMessageId,
+) -> Result<Option<Message>, CLIError> {
+    let pending: Vec<Value> = provider
+        .request::<[(); 1], Vec<Value>>("Filecoin.MpoolPending", [()])
+        .await?;
+
+    for signed_message in pending.into_iter() {
+        let cid = signed_message["CID"]["/"].as_str().map(String::from);
+        let message = serde_json::from_value::<MessageTxAPI>(signed_message)?.get_message();
+        let found = match id {
+            PendingMessageId::Cid(expected) => cid.as_ref() == Some(expected),
+            PendingMessageId::Nonce { sender, nonce } => {
+                message.from == *sender && message.sequence == *nonce
+            }
+        };
+        if found {
+            return Ok(Some(message));
+        }
+    }
+    Ok(None)
+}
+
+/// Replaces a message stuck in the mpool with a copy paying a higher
+/// premium, signed by `signer_address`. Returns the CID of the replacement.
+pub async fn bump_message(
+    provider: &Provider<Http>,
+    id: &PendingMessageId,
+    signature_method: &SignatureMethod,
+    signer_address: &str,
+) -> Result<String, CLIError> {
+    if let PendingMessageId::Cid(cid) = id {
+        if let Some(result) = search_message(provider, cid).await? {
+            return Err(CLIError::InvalidInput(format!(
+                "{} was already executed: {}",
+                id, result
+            )));
+        }
+    }
+
+    let pending = find_pending_message(provider, id)
+        .await?
+        .ok_or_else(|| CLIError::MessageNotPending(id.to_string()))?;
+    if pending.from != FilecoinAddress::from_str(signer_address)? {
+        return Err(CLIError::InvalidInput(format!(
+            "{} was sent by {}, it cannot be replaced by {}",
+            id, pending.from, signer_address
+        )));
+    }
+
+    let mut message = pending.clone();
+    message.gas_limit = 0;
+    message.gas_fee_cap = TokenAmount::default();
+    message.gas_premium = TokenAmount::default();
+    let gas_info = get_gas_info(message.clone(), provider.clone()).await?;
+
+    let gas_premium = std::cmp::max(
+        gas_info.gas_premium,
+        min_replacement_premium(&pending.gas_premium),
+    );
+    let gas_fee_cap = std::cmp::max(
+        std::cmp::max(gas_info.gas_fee_cap, pending.gas_fee_cap.clone()),
+        gas_premium.clone(),
+    );
+    // the replacement premium can raise the fee cap past the estimate the
+    // policy was checked against, so check the final values again
+    gas_policy().check_message(&TransactionGasInfo {
+        gas_limit: gas_info.gas_limit,
+        gas_fee_cap: gas_fee_cap.clone(),
+        gas_premium: gas_premium.clone(),
+    })?;
+    message.gas_limit = gas_info.gas_limit;
+    message.gas_premium = gas_premium;
+    message.gas_fee_cap = gas_fee_cap;
+
+    info!(
+        "Replacing {}: premium {} -> {}, fee cap {} -> {}",
+        id, pending.gas_premium, message.gas_premium, pending.gas_fee_cap, message.gas_fee_cap
+    );
+
+    let signed_message = sign_message_with_gas(signature_method, &message).await?;
+    push_mpool_message(provider, signed_message).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NonceAllocator;
+
+    #[test]
+    fn test_nonce_allocator() {
+        let mut nonces = NonceAllocator::starting_at("f01", 7);
+        assert_eq!(nonces.next(), 7);
+        assert_eq!(nonces.next(), 8);
+
+        // the last nonce is handed out again after a failed push
+        assert!(nonces.rollback(8).is_ok());
+        assert_eq!(nonces.next(), 8);
+
+        // rolling back an earlier nonce would leave a gap
+        assert!(nonces.rollback(7).is_err());
+        assert_eq!(nonces.next(), 9);
+    }
+}
@@ -0,0 +1,777 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use cid::Cid;
+use contract_bindings::payout_factory_native_addr::{
+    PayoutCall, PayoutFactoryNativeAddrCalls, PAYOUTFACTORYNATIVEADDR_ABI,
+};
+use contract_bindings::shared_types::FilAddress;
+use ethabi::token::{LenientTokenizer, Tokenizer};
+use ethers::abi::{AbiDecode, AbiEncode};
+use ethers::providers::{Http, Provider};
+use ethers::types::U256;
+use fil_actor_init::ExecParams;
+use fil_actor_multisig::{
+    AddSignerParams, ChangeNumApprovalsThresholdParams, ConstructorParams, LockBalanceParams,
+    Method, ProposeParams, RemoveSignerParams, SwapSignerParams,
+};
+use filecoin_signer::api::MessageParams;
+use filecoin_signer::{compute_proposal_hash, ProposalHashDataAPI};
+use fvm_ipld_encoding::{BytesDe, RawBytes};
+use fvm_shared::address::Address as FilecoinAddress;
+use fvm_shared::bigint::BigInt;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::message::Message;
+use log::info;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tabled::Tabled;
+
+use crate::error::CLIError;
+use crate::mpool::NonceAllocator;
+use crate::utils::{
+    batch_ranges, encode_calldata_params, format_fil, format_fil_address, format_table,
+    parse_payouts, sign_and_push_message, MultiSigTransaction, SignatureMethod,
+    MAX_PAYEES_PER_PAYOUT,
+};
+
+/// Method number of `InvokeContract` on EVM actors.
+const INVOKE_CONTRACT_METHOD: u64 = 3844450837;
+/// ID of the Init actor, which creates actors through `Exec`.
+const INIT_ACTOR_ID: u64 = 1;
+
+/// A row of the table describing a pending multisig transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Tabled)]
+pub struct ProposalField {
+    pub field: String,
+    pub value: String,
+}
+
+impl ProposalField {
+    fn new(field: &str, value: impl ToString) -> Self {
+        ProposalField {
+            field: field.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+/// Encodes a call of the `PayoutFactory` function `name` as `InvokeContract`
+/// params, parsing `args` according to the types of the function's inputs.
+///
+/// ```
+/// # use cli::multisig::encode_factory_call;
+/// let params = encode_factory_call(
+///     "grantRole",
+///     &[
+///         format!("0x{}", "00".repeat(32)),
+///         String::from("0x0000000000000000000000000000000000000001"),
+///     ],
+/// )
+/// .unwrap();
+/// // CBOR byte string header, selector and two words
+/// assert_eq!(params.len(), 2 + 4 + 64);
+/// ```
+pub fn encode_factory_call(name: &str, args: &[String]) -> Result<RawBytes, CLIError> {
+    let function = PAYOUTFACTORYNATIVEADDR_ABI
+        .function(name)
+        .map_err(CLIError::invalid_input)?;
+    if function.inputs.len() != args.len() {
+        return Err(CLIError::InvalidInput(format!(
+            "{} takes {} arguments, {} given",
+            name,
+            function.inputs.len(),
+            args.len()
+        )));
+    }
+    let tokens = function
+        .inputs
+        .iter()
+        .zip(args)
+        .map(|(input, arg)| {
+            LenientTokenizer::tokenize(&input.kind, arg).map_err(|error| {
+                CLIError::InvalidInput(format!(
+                    "invalid {} argument '{}': {}",
+                    input.name, arg, error
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>, CLIError>>()?;
+    let calldata = function
+        .encode_input(&tokens)
+        .map_err(CLIError::invalid_input)?;
+    Ok(RawBytes::new(encode_calldata_params(calldata)?))
+}
+
+/// Returns the code CID of the multisig actor in the manifest of the current
+/// network version.
+pub async fn multisig_code_cid(provider: &Provider<Http>) -> Result<Cid, CLIError> {
+    let network_version: u32 = provider
+        .request("Filecoin.StateNetworkVersion", [Value::Null])
+        .await?;
+    let code_cids: HashMap<String, Value> = provider
+        .request("Filecoin.StateActorCodeCIDs", [network_version])
+        .await?;
+    let code_cid = code_cids
+        .get("multisig")
+        .and_then(|cid| cid["/"].as_str())
+        .ok_or_else(|| {
+            CLIError::Rpc(format!(
+                "no multisig actor in the manifest of network version {}",
+                network_version
+            ))
+        })?;
+    Cid::try_from(code_cid).map_err(CLIError::rpc)
+}
+
+/// Creates a multisig actor through the Init actor and returns the CID of the
+/// `Exec` message. `value` is transferred to the new multisig.
+pub async fn create_multisig(
+    provider: &Provider<Http>,
+    signing_method: &SignatureMethod,
+    signing_address: &str,
+    params: ConstructorParams,
+    value: TokenAmount,
+    nonces: &mut NonceAllocator,
+) -> Result<String, CLIError> {
+    if params.num_approvals_threshold == 0
+        || params.num_approvals_threshold > params.signers.len() as u64
+    {
+        return Err(CLIError::InvalidInput(format!(
+            "threshold must be between 1 and the {} signers",
+            params.signers.len()
+        )));
+    }
+    let exec_params = ExecParams {
+        code_cid: multisig_code_cid(provider).await?,
+        constructor_params: MessageParams::MultisigConstructorParams(params).serialize()?,
+    };
+
+    let mut message = Message {
+        version: 0,
+        to: FilecoinAddress::new_id(INIT_ACTOR_ID),
+        from: FilecoinAddress::from_str(signing_address)?,
+        // set when the message is pushed
+        sequence: 0,
+        value,
+        gas_limit: 0,
+        gas_fee_cap: TokenAmount::from_atto(0),
+        gas_premium: TokenAmount::from_atto(0),
+        method_num: fil_actor_init::Method::Exec as u64,
+        params: MessageParams::ExecParams(exec_params).serialize()?,
+    };
+
+    sign_and_push_message(provider, signing_method, nonces, &mut message).await
+}
+
+/// Proposes a transaction on the multisig `actor_address` and returns the CID
+/// of the `Propose` message. The transaction executes once enough signers
+/// approved it.
+pub async fn propose(
+    provider: &Provider<Http>,
+    actor_address: &str,
+    signing_method: &SignatureMethod,
+    signing_address: &str,
+    params: ProposeParams,
+    nonces: &mut NonceAllocator,
+) -> Result<String, CLIError> {
+    info!(
+        "proposing to {}:\n{}",
+        actor_address,
+        format_table(describe_proposal(actor_address, &params))
+    );
+    let mut message = Message {
+        version: 0,
+        to: FilecoinAddress::from_str(actor_address)?,
+        from: FilecoinAddress::from_str(signing_address)?,
+        // set when the message is pushed
+        sequence: 0,
+        value: TokenAmount::from_atto(0),
+        gas_limit: 0,
+        gas_fee_cap: TokenAmount::from_atto(0),
+        gas_premium: TokenAmount::from_atto(0),
+        method_num: Method::Propose as u64,
+        params: MessageParams::ProposeParams(params).serialize()?,
+    };
+
+    sign_and_push_message(provider, signing_method, nonces, &mut message).await
+}
+
+/// Proposes a call of one of the multisig's own methods, such as adding a
+/// signer or changing the threshold, which the multisig applies to itself
+/// once approved. The method is inferred from `params`.
+pub async fn propose_self_call(
+    provider: &Provider<Http>,
+    actor_address: &str,
+    signing_method: &SignatureMethod,
+    signing_address: &str,
+    params: MessageParams,
+    nonces: &mut NonceAllocator,
+) -> Result<String, CLIError> {
+    let method = self_call_method(&params)?;
+    let params = ProposeParams {
+        to: FilecoinAddress::from_str(actor_address)?,
+        value: TokenAmount::from_atto(0),
+        method: method as u64,
+        params: params.serialize()?,
+    };
+    propose(
+        provider,
+        actor_address,
+        signing_method,
+        signing_address,
+        params,
+        nonces,
+    )
+    .await
+}
+
+fn self_call_method(params: &MessageParams) -> Result<Method, CLIError> {
+    match params {
+        MessageParams::AddSignerParams(_) => Ok(Method::AddSigner),
+        MessageParams::RemoveSignerParams(_) => Ok(Method::RemoveSigner),
+        MessageParams::SwapSignerParams(_) => Ok(Method::SwapSigner),
+        MessageParams::ChangeNumApprovalsThresholdParams(_) => {
+            Ok(Method::ChangeNumApprovalsThreshold)
+        }
+        MessageParams::LockBalanceParams(_) => Ok(Method::LockBalance),
+        _ => Err(CLIError::InvalidInput(String::from(
+            "params are not those of a multisig method",
+        ))),
+    }
+}
+
+/// Decodes the base64 params of a pending transaction, as returned by
+/// `Filecoin.MsigGetPending`.
+pub fn decode_pending_params(transaction: &MultiSigTransaction) -> Result<RawBytes, CLIError> {
+    match &transaction.params {
+        Some(params) => Ok(RawBytes::new(STANDARD.decode(params)?)),
+        None => Ok(RawBytes::default()),
+    }
+}
+
+/// Computes the hash of a pending transaction, which approvals and
+/// cancellations must match. It is encoded as base64.
+pub fn proposal_hash(transaction: &MultiSigTransaction) -> Result<String, CLIError> {
+    let requester = match transaction.approved.first() {
+        Some(requester) => Some(FilecoinAddress::from_str(requester)?),
+        None => None,
+    };
+    let hash = compute_proposal_hash(ProposalHashDataAPI {
+        requester,
+        to: FilecoinAddress::from_str(&transaction.to)?,
+        value: TokenAmount::from_atto(BigInt::from_str(&transaction.value)?),
+        method: transaction.method,
+        params: decode_pending_params(transaction)?,
+    })?;
+    Ok(hash)
+}
+
+/// Raw bytes of the proposal hash, passed with an approval so that it only
+/// applies to the proposal that was reviewed.
+pub fn proposal_hash_bytes(transaction: &MultiSigTransaction) -> Result<Vec<u8>, CLIError> {
+    Ok(STANDARD.decode(proposal_hash(transaction)?)?)
+}
+
+/// Decodes the `payout` call proposed by a pending transaction, whose params
+/// are the `InvokeContract` calldata behind a CBOR byte string header.
+pub fn decode_payout_proposal(transaction: &MultiSigTransaction) -> Result<PayoutCall, CLIError> {
+    let mismatch = |reason: String| CLIError::ProposalMismatch(transaction.id, reason);
+    if transaction.method != INVOKE_CONTRACT_METHOD {
+        return Err(mismatch(format!(
+            "method {} is not InvokeContract",
+            transaction.method
+        )));
+    }
+    let calldata: BytesDe = deserialize(&decode_pending_params(transaction)?).map_err(mismatch)?;
+    match PayoutFactoryNativeAddrCalls::decode(&calldata.0) {
+        Ok(PayoutFactoryNativeAddrCalls::Payout(payout)) => Ok(payout),
+        Ok(call) => Err(mismatch(format!("{} is not a payout", call))),
+        Err(error) => Err(mismatch(format!("could not decode calldata: {}", error))),
+    }
+}
+
+/// Payout batches `new-payout` would propose from a payout CSV, to check
+/// pending proposals against before approving them.
+pub struct ExpectedPayouts {
+    /// ID address of the factory the payouts are sent to.
+    factory_id: String,
+    batches: Vec<PayoutCall>,
+    /// Id of the proposal matched with each batch, so that a batch proposed
+    /// twice is not paid out twice.
+    matched: Vec<Option<u64>>,
+}
+
+impl ExpectedPayouts {
+    /// Splits `payees` and `shares` into the batches `new-payout` proposes
+    /// to the factory with ID address `factory_id`.
+    pub fn new(factory_id: &str, payees: &[String], shares: &[Decimal]) -> Result<Self, CLIError> {
+        let mut batches = Vec::new();
+        for (start, end) in batch_ranges(payees.len(), MAX_PAYEES_PER_PAYOUT) {
+            let (payees, shares) =
+                parse_payouts(&payees[start..end].to_vec(), &shares[start..end].to_vec())?;
+            let total_value = shares.iter().fold(U256::zero(), |acc, share| acc + share);
+            batches.push(PayoutCall {
+                payees,
+                shares,
+                total_value,
+            });
+        }
+        let matched = vec![None; batches.len()];
+        Ok(ExpectedPayouts {
+            factory_id: factory_id.to_string(),
+            batches,
+            matched,
+        })
+    }
+
+    /// Checks that a pending transaction sends no funds to the factory and
+    /// proposes the payout of a batch no other proposal has matched, by
+    /// rebuilding the calldata of every batch and comparing it to the
+    /// proposal. `to_id` is the ID address of the transaction target.
+    pub fn verify(
+        &mut self,
+        transaction: &MultiSigTransaction,
+        to_id: &str,
+    ) -> Result<(), CLIError> {
+        let mismatch = |reason: String| CLIError::ProposalMismatch(transaction.id, reason);
+        if to_id != self.factory_id {
+            return Err(mismatch(format!(
+                "it is sent to {}, expected the factory {}",
+                transaction.to, self.factory_id
+            )));
+        }
+        let value = BigInt::from_str(&transaction.value)?;
+        if value != BigInt::from(0) {
+            return Err(mismatch(format!(
+                "it sends {} FIL, expected 0 FIL",
+                TokenAmount::from_atto(value)
+            )));
+        }
+
+        let proposed = decode_payout_proposal(transaction)?;
+        let proposed_calldata = PayoutFactoryNativeAddrCalls::Payout(proposed.clone()).encode();
+        let mut duplicate_of = None;
+        for (batch, expected) in self.batches.iter().enumerate() {
+            if PayoutFactoryNativeAddrCalls::Payout(expected.clone()).encode() != proposed_calldata
+            {
+                continue;
+            }
+            match self.matched[batch] {
+                Some(id) if id != transaction.id => duplicate_of = Some(id),
+                _ => {
+                    self.matched[batch] = Some(transaction.id);
+                    return Ok(());
+                }
+            }
+        }
+        if let Some(id) = duplicate_of {
+            return Err(mismatch(format!(
+                "it proposes the same payout as transaction {}",
+                id
+            )));
+        }
+
+        // compare against the batch starting with the same payee, if any
+        let expected = self
+            .batches
+            .iter()
+            .find(|batch| batch.payees.first() == proposed.payees.first())
+            .or_else(|| self.batches.first());
+        let reason = match expected {
+            Some(expected) => payout_differences(expected, &proposed).join(", "),
+            None => String::from("the CSV has no payouts"),
+        };
+        Err(mismatch(reason))
+    }
+}
+
+fn payout_differences(expected: &PayoutCall, proposed: &PayoutCall) -> Vec<String> {
+    let mut differences = Vec::new();
+    if expected.payees.len() != proposed.payees.len() {
+        differences.push(format!(
+            "{} payees proposed, {} expected",
+            proposed.payees.len(),
+            expected.payees.len()
+        ));
+    }
+    let payees = expected.payees.iter().zip(proposed.payees.iter());
+    if let Some(index) = payees
+        .clone()
+        .position(|(expected, proposed)| expected != proposed)
+    {
+        let (expected, proposed) = payees.clone().nth(index).unwrap();
+        differences.push(format!(
+            "payee {} is {}, expected {}",
+            index,
+            format_fil_address(proposed),
+            format_fil_address(expected)
+        ));
+    }
+    let shares = expected.shares.iter().zip(proposed.shares.iter());
+    if let Some(index) = shares
+        .clone()
+        .position(|(expected, proposed)| expected != proposed)
+    {
+        let (expected, proposed) = shares.clone().nth(index).unwrap();
+        differences.push(format!(
+            "share {} is {} FIL, expected {} FIL",
+            index,
+            format_fil(*proposed),
+            format_fil(*expected)
+        ));
+    }
+    if expected.total_value != proposed.total_value {
+        differences.push(format!(
+            "total is {} FIL, expected {} FIL",
+            format_fil(proposed.total_value),
+            format_fil(expected.total_value)
+        ));
+    }
+    if differences.is_empty() {
+        differences.push(String::from("calldata differs"));
+    }
+    differences
+}
+
+/// Describes a pending transaction of the multisig `actor_id` for signers to
+/// review before approving it: the decoded call, its target, the approvals
+/// against `threshold` and the proposal hash.
+pub fn describe_pending_transaction(
+    actor_id: &str,
+    transaction: &MultiSigTransaction,
+    threshold: u64,
+) -> Result<Vec<ProposalField>, CLIError> {
+    let value = TokenAmount::from_atto(BigInt::from_str(&transaction.value)?);
+    let mut fields = vec![
+        ProposalField::new("ID", transaction.id),
+        ProposalField::new("To", &transaction.to),
+        ProposalField::new("Value", format!("{} FIL", value)),
+    ];
+
+    let params = decode_pending_params(transaction)?;
+    let to_self = is_same_actor(actor_id, &transaction.to);
+    fields.extend(describe_call(to_self, transaction.method, &params));
+
+    fields.push(ProposalField::new(
+        "Approvals",
+        format!(
+            "{}/{} ({})",
+            transaction.approved.len(),
+            threshold,
+            transaction.approved.join(", ")
+        ),
+    ));
+    fields.push(ProposalField::new(
+        "Proposal Hash",
+        proposal_hash(transaction)?,
+    ));
+    Ok(fields)
+}
+
+/// Describes a transaction about to be proposed to the multisig
+/// `actor_address`, like `describe_pending_transaction` does once it is
+/// pending.
+pub fn describe_proposal(actor_address: &str, params: &ProposeParams) -> Vec<ProposalField> {
+    let to = params.to.to_string();
+    let mut fields = vec![
+        ProposalField::new("To", &to),
+        ProposalField::new("Value", format!("{} FIL", params.value)),
+    ];
+    let to_self = is_same_actor(actor_address, &to);
+    fields.extend(describe_call(to_self, params.method, &params.params));
+    fields
+}
+
+/// Whether the addresses refer to the same actor, comparing the strings as
+/// the ID and robust addresses of an actor cannot be resolved offline.
+fn is_same_actor(actor_id: &str, to: &str) -> bool {
+    actor_id.trim_start_matches(['f', 't']) == to.trim_start_matches(['f', 't'])
+}
+
+/// Decodes the call of a pending transaction. Multisig methods are decoded
+/// when the transaction targets the multisig itself, and `InvokeContract`
+/// params are decoded as `PayoutFactory` calls.
+pub fn describe_call(to_self: bool, method: u64, params: &RawBytes) -> Vec<ProposalField> {
+    let described = match method {
+        0 => Ok(vec![ProposalField::new("Call", "send")]),
+        INVOKE_CONTRACT_METHOD => describe_contract_call(params),
+        method if to_self => describe_multisig_call(method, params),
+        method => Err(format!("unknown method {}", method)),
+    };
+    described.unwrap_or_else(|error| {
+        vec![
+            ProposalField::new("Method", method),
+            ProposalField::new("Call", format!("could not decode params: {}", error)),
+            ProposalField::new("Params", hex::encode(params.bytes())),
+        ]
+    })
+}
+
+fn deserialize<T: DeserializeOwned>(params: &RawBytes) -> Result<T, String> {
+    params.deserialize().map_err(|error| error.to_string())
+}
+
+fn describe_multisig_call(method: u64, params: &RawBytes) -> Result<Vec<ProposalField>, String> {
+    let fields = if method == Method::AddSigner as u64 {
+        let params: AddSignerParams = deserialize(params)?;
+        vec![
+            ProposalField::new("Call", "AddSigner"),
+            ProposalField::new("Signer", params.signer),
+            ProposalField::new("Increase Threshold", params.increase),
+        ]
+    } else if method == Method::RemoveSigner as u64 {
+        let params: RemoveSignerParams = deserialize(params)?;
+        vec![
+            ProposalField::new("Call", "RemoveSigner"),
+            ProposalField::new("Signer", params.signer),
+            ProposalField::new("Decrease Threshold", params.decrease),
+        ]
+    } else if method == Method::SwapSigner as u64 {
+        let params: SwapSignerParams = deserialize(params)?;
+        vec![
+            ProposalField::new("Call", "SwapSigner"),
+            ProposalField::new("From", params.from),
+            ProposalField::new("To", params.to),
+        ]
+    } else if method == Method::ChangeNumApprovalsThreshold as u64 {
+        let params: ChangeNumApprovalsThresholdParams = deserialize(params)?;
+        vec![
+            ProposalField::new("Call", "ChangeNumApprovalsThreshold"),
+            ProposalField::new("New Threshold", params.new_threshold),
+        ]
+    } else if method == Method::LockBalance as u64 {
+        let params: LockBalanceParams = deserialize(params)?;
+        vec![
+            ProposalField::new("Call", "LockBalance"),
+            ProposalField::new("Start Epoch", params.start_epoch),
+            ProposalField::new("Unlock Duration", params.unlock_duration),
+            ProposalField::new("Amount", format!("{} FIL", params.amount)),
+        ]
+    } else {
+        return Err(format!("unknown multisig method {}", method));
+    };
+    Ok(fields)
+}
+
+fn describe_contract_call(params: &RawBytes) -> Result<Vec<ProposalField>, String> {
+    let calldata: BytesDe = deserialize(params)?;
+    let call = PayoutFactoryNativeAddrCalls::decode(&calldata.0).map_err(|e| e.to_string())?;
+    let fields = match call {
+        PayoutFactoryNativeAddrCalls::Payout(payout) => {
+            let total = format_fil(payout.total_value);
+            let mut fields = vec![
+                ProposalField::new("Call", "payout"),
+                ProposalField::new("Payees", payout.payees.len()),
+                ProposalField::new("Total", format!("{} FIL", total)),
+            ];
+            let payees = payout.payees.iter().zip(payout.shares.iter());
+            if let Some((payee, share)) = payees.clone().next() {
+                fields.push(ProposalField::new(
+                    "First Payee",
+                    describe_payee(payee, share),
+                ));
+            }
+            if let Some((payee, share)) = payees.last() {
+                fields.push(ProposalField::new(
+                    "Last Payee",
+                    describe_payee(payee, share),
+                ));
+            }
+            fields
+        }
+        PayoutFactoryNativeAddrCalls::GrantRole(grant) => vec![
+            ProposalField::new("Call", "grantRole"),
+            ProposalField::new("Role", format!("0x{}", hex::encode(grant.role))),
+            ProposalField::new("Account", format!("{:?}", grant.account)),
+        ],
+        PayoutFactoryNativeAddrCalls::RevokeRole(revoke) => vec![
+            ProposalField::new("Call", "revokeRole"),
+            ProposalField::new("Role", format!("0x{}", hex::encode(revoke.role))),
+            ProposalField::new("Account", format!("{:?}", revoke.account)),
+        ],
+        call => vec![ProposalField::new("Call", call)],
+    };
+    Ok(fields)
+}
+
+fn describe_payee(payee: &FilAddress, share: &U256) -> String {
+    format!("{} ({} FIL)", format_fil_address(payee), format_fil(*share))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        describe_call, encode_factory_call, is_same_actor, self_call_method, ExpectedPayouts,
+        ProposalField,
+    };
+    use crate::error::CLIError;
+    use crate::utils::{encode_calldata_params, MultiSigTransaction, ATTO_FIL};
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use contract_bindings::payout_factory_native_addr::{PayoutCall, PayoutFactoryNativeAddrCalls};
+    use contract_bindings::shared_types::FilAddress;
+    use ethers::abi::AbiEncode;
+    use ethers::types::U256;
+    use fil_actor_multisig::{ChangeNumApprovalsThresholdParams, Method, SwapSignerParams};
+    use filecoin_signer::api::MessageParams;
+    use fvm_ipld_encoding::RawBytes;
+    use fvm_shared::address::Address as FilecoinAddress;
+    use rust_decimal::Decimal;
+
+    fn field<'a>(fields: &'a [ProposalField], name: &str) -> &'a str {
+        &fields
+            .iter()
+            .find(|field| field.field == name)
+            .unwrap()
+            .value
+    }
+
+    #[test]
+    fn test_describe_payout_call() {
+        let payees = [100, 101, 102]
+            .iter()
+            .map(|id| FilAddress {
+                data: FilecoinAddress::new_id(*id).to_bytes().into(),
+            })
+            .collect();
+        let call = PayoutFactoryNativeAddrCalls::Payout(PayoutCall {
+            payees,
+            shares: vec![U256::from(ATTO_FIL); 3],
+            total_value: U256::from(3 * ATTO_FIL),
+        });
+        let params = RawBytes::new(encode_calldata_params(call.encode()).unwrap());
+
+        let fields = describe_call(false, 3844450837, &params);
+        assert_eq!(field(&fields, "Call"), "payout");
+        assert_eq!(field(&fields, "Payees"), "3");
+        assert_eq!(field(&fields, "Total"), "3 FIL");
+        assert!(field(&fields, "First Payee").ends_with("100 (1 FIL)"));
+        assert!(field(&fields, "Last Payee").ends_with("102 (1 FIL)"));
+    }
+
+    #[test]
+    fn test_describe_multisig_call() {
+        let params =
+            RawBytes::serialize(ChangeNumApprovalsThresholdParams { new_threshold: 3 }).unwrap();
+        let fields = describe_call(true, Method::ChangeNumApprovalsThreshold as u64, &params);
+        assert_eq!(field(&fields, "Call"), "ChangeNumApprovalsThreshold");
+        assert_eq!(field(&fields, "New Threshold"), "3");
+
+        // multisig methods of other actors are not decoded
+        let fields = describe_call(false, Method::ChangeNumApprovalsThreshold as u64, &params);
+        assert!(field(&fields, "Call").starts_with("could not decode params"));
+    }
+
+    #[test]
+    fn test_expected_payouts() {
+        let payees: Vec<String> = vec!["f0100".into(), "f0101".into()];
+        let shares = vec![Decimal::new(15, 1), Decimal::ONE];
+        let proposal = |id: u64, shares: Vec<u128>| {
+            let call = PayoutFactoryNativeAddrCalls::Payout(PayoutCall {
+                payees: [100, 101]
+                    .iter()
+                    .map(|id| FilAddress {
+                        data: FilecoinAddress::new_id(*id).to_bytes().into(),
+                    })
+                    .collect(),
+                shares: shares.iter().map(|share| U256::from(*share)).collect(),
+                total_value: U256::from(shares.iter().sum::<u128>()),
+            });
+            MultiSigTransaction {
+                id,
+                to: String::from("f410fabc"),
+                value: String::from("0"),
+                method: 3844450837,
+                params: Some(STANDARD.encode(encode_calldata_params(call.encode()).unwrap())),
+                approved: vec![],
+            }
+        };
+        let expected = || ExpectedPayouts::new("f01000", &payees, &shares).unwrap();
+
+        let matching = proposal(7, vec![3 * ATTO_FIL / 2, ATTO_FIL]);
+        let mut payouts = expected();
+        assert!(payouts.verify(&matching, "f01000").is_ok());
+        // checking the same proposal again is not a duplicate
+        assert!(payouts.verify(&matching, "f01000").is_ok());
+
+        let duplicate = proposal(8, vec![3 * ATTO_FIL / 2, ATTO_FIL]);
+        match payouts.verify(&duplicate, "f01000") {
+            Err(CLIError::ProposalMismatch(8, reason)) => {
+                assert_eq!(reason, "it proposes the same payout as transaction 7");
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let tampered = proposal(7, vec![3 * ATTO_FIL / 2, 2 * ATTO_FIL]);
+        match expected().verify(&tampered, "f01000") {
+            Err(CLIError::ProposalMismatch(7, reason)) => {
+                assert!(reason.contains("share 1 is 2 FIL, expected 1 FIL"));
+                assert!(reason.contains("total is 3.5 FIL, expected 2.5 FIL"));
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        match expected().verify(&matching, "f01001") {
+            Err(CLIError::ProposalMismatch(7, reason)) => {
+                assert_eq!(
+                    reason,
+                    "it is sent to f410fabc, expected the factory f01000"
+                );
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let mut funded = proposal(7, vec![3 * ATTO_FIL / 2, ATTO_FIL]);
+        funded.value = ATTO_FIL.to_string();
+        match expected().verify(&funded, "f01000") {
+            Err(CLIError::ProposalMismatch(7, reason)) => {
+                assert!(reason.starts_with("it sends 1"));
+                assert!(reason.ends_with("FIL, expected 0 FIL"));
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_self_call_method() {
+        let params = MessageParams::SwapSignerParams(SwapSignerParams {
+            from: FilecoinAddress::new_id(100),
+            to: FilecoinAddress::new_id(101),
+        });
+        assert!(matches!(self_call_method(&params), Ok(Method::SwapSigner)));
+
+        let params = MessageParams::MessageParamsSerialized(String::new());
+        assert!(self_call_method(&params).is_err());
+    }
+
+    #[test]
+    fn test_encode_factory_call() {
+        let params = encode_factory_call(
+            "revokeRole",
+            &[
+                format!("0x{}", "00".repeat(32)),
+                String::from("0x0000000000000000000000000000000000000001"),
+            ],
+        )
+        .unwrap();
+        let fields = describe_call(false, 3844450837, &params);
+        assert_eq!(field(&fields, "Call"), "revokeRole");
+        assert_eq!(
+            field(&fields, "Account"),
+            "0x0000000000000000000000000000000000000001"
+        );
+
+        assert!(encode_factory_call("revokeRole", &[]).is_err());
+        assert!(encode_factory_call("notAFunction", &[]).is_err());
+    }
+
+    #[test]
+    fn test_is_same_actor() {
+        assert!(is_same_actor("f01234", "t01234"));
+        assert!(!is_same_actor("f01234", "f012345"));
+    }
+}
@@ -0,0 +1,206 @@
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use contract_bindings::payout_factory_native_addr::PayoutFactoryNativeAddr as PayoutFactory;
+use contract_bindings::shared_types::FilAddress;
+use ethers::abi::Address;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::U256;
+use fevm_utils::filecoin_to_eth_address;
+use log::info;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::error::CLIError;
+use crate::mpool::NonceAllocator;
+use crate::utils::{
+    batch_ranges, build_propose_payout_message, cbor_header_index, estimate_message_gas,
+    format_table, format_u256, parse_payouts, payout_calldata, MAX_PAYEES_PER_PAYOUT,
+    PARAMS_CBOR_HEADER,
+};
+
+/// Planned deployment of a single payout batch.
+#[derive(Debug, Clone, Serialize, Tabled)]
+pub struct BatchPlan {
+    pub batch: usize,
+    pub start_index: usize,
+    pub end_index: usize,
+    pub payees: usize,
+    /// Total FIL sent to the batch's payout contract.
+    pub total_fil: Decimal,
+    pub calldata_bytes: usize,
+    /// CBOR byte string header used to wrap the calldata.
+    pub cbor_header: String,
+    /// Gas estimate, or the estimation error.
+    pub gas_estimate: String,
+}
+
+/// Deployment plan of a payout, computed without sending anything.
+#[derive(Debug, Clone, Serialize)]
+pub struct PayoutPlan {
+    pub payee_count: usize,
+    pub batch_count: usize,
+    /// Total FIL the factory needs to hold to deploy every batch.
+    pub total_fil: Decimal,
+    pub batches: Vec<BatchPlan>,
+}
+
+impl PayoutPlan {
+    /// Logs the plan and writes it as JSON to `plan_file` if given.
+    pub fn report(&self, plan_file: &Option<PathBuf>) -> Result<(), CLIError> {
+        info!(
+            "\n\n  Payout plan: {} payees in {} batches, {} FIL needed in the factory \n\n{}",
+            self.payee_count,
+            self.batch_count,
+            self.total_fil,
+            format_table(self.batches.iter())
+        );
+        if let Some(plan_file) = plan_file {
+            fs::write(plan_file, serde_json::to_string_pretty(self)?)?;
+            info!("Wrote payout plan to {:?}", plan_file);
+        }
+        Ok(())
+    }
+}
+
+fn plan_batch(
+    batch: usize,
+    (start_index, end_index): (usize, usize),
+    shares: &[U256],
+    calldata_bytes: usize,
+    gas_estimate: String,
+) -> Result<BatchPlan, CLIError> {
+    let total = shares.iter().fold(U256::zero(), |acc, x| acc + x);
+    Ok(BatchPlan {
+        batch,
+        start_index,
+        end_index,
+        payees: end_index - start_index,
+        total_fil: format_u256(total)?,
+        calldata_bytes,
+        cbor_header: String::from(PARAMS_CBOR_HEADER[cbor_header_index(calldata_bytes)]),
+        gas_estimate,
+    })
+}
+
+/// Size of the `payout` calldata of a batch, before it is wrapped in CBOR.
+fn payout_calldata_len<S: Middleware + 'static>(
+    factory: &PayoutFactory<S>,
+    payees: &[FilAddress],
+    shares: &[U256],
+) -> Result<usize, CLIError> {
+    Ok(payout_calldata(factory, payees.to_vec(), shares.to_vec())?.len())
+}
+
+fn collect_plan(payee_count: usize, batches: Vec<BatchPlan>) -> PayoutPlan {
+    PayoutPlan {
+        payee_count,
+        batch_count: batches.len(),
+        total_fil: batches.iter().map(|batch| batch.total_fil).sum(),
+        batches,
+    }
+}
+
+/// Plans a `new-payout` deployment, estimating the gas of each `payout` call
+/// sent directly to the factory by `client`.
+pub async fn plan_new_payout<S: Middleware + 'static>(
+    client: Arc<S>,
+    factory_addr: &str,
+    payees: &Vec<String>,
+    shares: &Vec<Decimal>,
+) -> Result<PayoutPlan, CLIError> {
+    let factory = PayoutFactory::new(Address::from_str(factory_addr)?, client.clone());
+    let (parsed_payees, parsed_shares) = parse_payouts(payees, shares)?;
+
+    let mut batches = Vec::new();
+    for (batch, (start, end)) in batch_ranges(payees.len(), MAX_PAYEES_PER_PAYOUT)
+        .into_iter()
+        .enumerate()
+    {
+        let batch_shares = parsed_shares[start..end].to_vec();
+        let total = batch_shares.iter().fold(U256::zero(), |acc, x| acc + x);
+        let payout_call = factory.payout(parsed_payees[start..end].to_vec(), batch_shares, total);
+        let calldata_bytes = payout_calldata_len(
+            &factory,
+            &parsed_payees[start..end],
+            &parsed_shares[start..end],
+        )?;
+
+        let gas_estimate = match client.estimate_gas(&payout_call.tx, None).await {
+            Ok(gas) => gas.to_string(),
+            Err(error) => format!("failed: {}", error),
+        };
+        batches.push(plan_batch(
+            batch,
+            (start, end),
+            &parsed_shares[start..end],
+            calldata_bytes,
+            gas_estimate,
+        )?);
+    }
+
+    Ok(collect_plan(payees.len(), batches))
+}
+
+/// Plans a `propose-new-payout` deployment, estimating the gas of each
+/// multisig `Propose` message sent by `signer_address`.
+pub async fn plan_propose_payout(
+    actor_address: &str,
+    receiver_address: &str,
+    payees: &Vec<String>,
+    shares: &Vec<Decimal>,
+    provider: &Provider<Http>,
+    rpc_url: &str,
+    signer_address: &str,
+) -> Result<PayoutPlan, CLIError> {
+    let (parsed_payees, parsed_shares) = parse_payouts(payees, shares)?;
+    let factory_addr_eth = filecoin_to_eth_address(receiver_address, rpc_url)
+        .await
+        .map_err(CLIError::rpc)?;
+    let factory = PayoutFactory::new(
+        Address::from_str(&factory_addr_eth)?,
+        Arc::new(provider.clone()),
+    );
+    let mut nonces = NonceAllocator::new(provider, signer_address).await?;
+
+    let mut batches = Vec::new();
+    for (batch, (start, end)) in batch_ranges(payees.len(), MAX_PAYEES_PER_PAYOUT)
+        .into_iter()
+        .enumerate()
+    {
+        let message = build_propose_payout_message(
+            actor_address,
+            receiver_address,
+            parsed_payees[start..end].to_vec(),
+            parsed_shares[start..end].to_vec(),
+            provider,
+            rpc_url,
+            signer_address,
+            nonces.next(),
+        )
+        .await?;
+        let calldata_bytes = payout_calldata_len(
+            &factory,
+            &parsed_payees[start..end],
+            &parsed_shares[start..end],
+        )?;
+
+        // the raw estimate, like the EVM plan reports, not the policy's limit
+        let gas_estimate = match estimate_message_gas(message, provider.clone()).await {
+            Ok(gas_info) => gas_info.gas_limit.to_string(),
+            Err(error) => format!("failed: {}", error),
+        };
+        batches.push(plan_batch(
+            batch,
+            (start, end),
+            &parsed_shares[start..end],
+            calldata_bytes,
+            gas_estimate,
+        )?);
+    }
+
+    Ok(collect_plan(payees.len(), batches))
+}
@@ -0,0 +1,250 @@
+use base64::Engine;
+use ethers::providers::{Http, Provider};
+use fil_actor_init::ExecReturn;
+use fil_actor_multisig::{ApproveReturn, ProposeReturn};
+use fvm_ipld_encoding::RawBytes;
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::CLIError;
+use crate::utils::TransactionDetails;
+
+/// Number of epochs a message must be buried under before it is reported.
+const MESSAGE_CONFIDENCE: u64 = 5;
+/// Lotus sentinel to search the whole chain for a message.
+const LOOKBACK_NO_LIMIT: i64 = -1;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MessageReceipt {
+    exit_code: i64,
+    #[serde(rename = "Return")]
+    return_data: Option<String>,
+    gas_used: i64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MessageLookup {
+    message: TransactionDetails,
+    receipt: MessageReceipt,
+    height: i64,
+}
+
+/// Outcome of a Filecoin message included on chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageResult {
+    /// CID of the executed message. It differs from the pushed CID if the
+    /// message was replaced.
+    pub cid: String,
+    pub height: i64,
+    pub exit_code: i64,
+    pub gas_used: i64,
+    pub return_data: Vec<u8>,
+}
+
+impl MessageResult {
+    fn from_lookup(lookup: MessageLookup) -> Result<Self, CLIError> {
+        let return_data = match lookup.receipt.return_data {
+            Some(data) => base64::engine::general_purpose::STANDARD.decode(data)?,
+            None => vec![],
+        };
+        Ok(MessageResult {
+            cid: lookup.message.field,
+            height: lookup.height,
+            exit_code: lookup.receipt.exit_code,
+            gas_used: lookup.receipt.gas_used,
+            return_data,
+        })
+    }
+
+    /// Fails if the message did not execute successfully.
+    pub fn ensure_success(&self) -> Result<(), CLIError> {
+        if self.exit_code != 0 {
+            return Err(CLIError::MessageFailed(self.cid.clone(), self.exit_code));
+        }
+        Ok(())
+    }
+
+    /// Decodes the return value of a multisig `Propose` message.
+    pub fn propose_return(&self) -> Result<ProposeReturn, CLIError> {
+        RawBytes::new(self.return_data.clone())
+            .deserialize()
+            .map_err(|error| CLIError::Other(format!("invalid Propose return: {}", error)))
+    }
+
+    /// Decodes the return value of an Init actor `Exec` message.
+    pub fn exec_return(&self) -> Result<ExecReturn, CLIError> {
+        RawBytes::new(self.return_data.clone())
+            .deserialize()
+            .map_err(|error| CLIError::Other(format!("invalid Exec return: {}", error)))
+    }
+
+    /// Decodes the return value of a multisig `Approve` message.
+    pub fn approve_return(&self) -> Result<ApproveReturn, CLIError> {
+        RawBytes::new(self.return_data.clone())
+            .deserialize()
+            .map_err(|error| CLIError::Other(format!("invalid Approve return: {}", error)))
+    }
+}
+
+impl std::fmt::Display for MessageResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "message {} executed at height {} with exit code {}, using {} gas",
+            self.cid, self.height, self.exit_code, self.gas_used
+        )
+    }
+}
+
+/// Looks up a message that was already included on chain, returning `None`
+/// if it has not been executed yet.
+pub async fn search_message(
+    provider: &Provider<Http>,
+    cid: &str,
+) -> Result<Option<MessageResult>, CLIError> {
+    let cid = TransactionDetails {
+        field: cid.to_string(),
+    };
+    let lookup: Value = provider
+        .request::<((), TransactionDetails, i64, bool), Value>(
+            "Filecoin.StateSearchMsg",
+            ((), cid, LOOKBACK_NO_LIMIT, true),
+        )
+        .await?;
+    if lookup.is_null() {
+        return Ok(None);
+    }
+    Ok(Some(MessageResult::from_lookup(serde_json::from_value(
+        lookup,
+    )?)?))
+}
+
+/// Blocks until a pushed message is executed and buried under
+/// `MESSAGE_CONFIDENCE` epochs, then logs and returns its receipt.
+pub async fn wait_for_message(
+    provider: &Provider<Http>,
+    cid: &str,
+) -> Result<MessageResult, CLIError> {
+    info!("Waiting for message {} to be included on chain", cid);
+    let cid = TransactionDetails {
+        field: cid.to_string(),
+    };
+    let lookup: MessageLookup = provider
+        .request::<(TransactionDetails, u64, i64, bool), MessageLookup>(
+            "Filecoin.StateWaitMsg",
+            (cid, MESSAGE_CONFIDENCE, LOOKBACK_NO_LIMIT, true),
+        )
+        .await?;
+    let result = MessageResult::from_lookup(lookup)?;
+    info!("{}", result);
+    Ok(result)
+}
+
+/// Waits for a multisig `Propose` message and reports the id of the new
+/// transaction, which the other signers need to approve it.
+pub async fn wait_for_proposal(
+    provider: &Provider<Http>,
+    cid: &str,
+) -> Result<ProposeReturn, CLIError> {
+    let result = wait_for_message(provider, cid).await?;
+    result.ensure_success()?;
+    let proposal = result.propose_return()?;
+    info!(
+        "Proposal created with transaction id {} (applied: {})",
+        proposal.txn_id.0, proposal.applied
+    );
+    Ok(proposal)
+}
+
+/// Waits for the Init actor `Exec` message creating a multisig and reports
+/// the addresses of the new actor.
+pub async fn wait_for_multisig_creation(
+    provider: &Provider<Http>,
+    cid: &str,
+) -> Result<ExecReturn, CLIError> {
+    let result = wait_for_message(provider, cid).await?;
+    result.ensure_success()?;
+    let created = result.exec_return()?;
+    info!(
+        "Multisig created with robust address {} and ID address {}",
+        created.robust_address, created.id_address
+    );
+    Ok(created)
+}
+
+/// Waits for a multisig `Approve` message and reports whether the approval
+/// executed the transaction.
+pub async fn wait_for_approval(
+    provider: &Provider<Http>,
+    cid: &str,
+) -> Result<ApproveReturn, CLIError> {
+    let result = wait_for_message(provider, cid).await?;
+    result.ensure_success()?;
+    let approval = result.approve_return()?;
+    info!(
+        "Approval applied the transaction: {} (exit code {})",
+        approval.applied,
+        approval.code.value()
+    );
+    Ok(approval)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MessageLookup, MessageResult};
+    use fil_actor_init::ExecReturn;
+    use fil_actor_multisig::{ProposeReturn, TxnID};
+    use fvm_ipld_encoding::{to_vec, RawBytes};
+    use fvm_shared::address::Address;
+    use fvm_shared::error::ExitCode;
+
+    #[test]
+    fn test_decode_propose_receipt() {
+        let ret = to_vec(&ProposeReturn {
+            txn_id: TxnID(42),
+            applied: false,
+            code: ExitCode::OK,
+            ret: RawBytes::default(),
+        })
+        .unwrap();
+        let lookup: MessageLookup = serde_json::from_value(serde_json::json!({
+            "Message": { "/": "bafy2bzacea" },
+            "Receipt": {
+                "ExitCode": 0,
+                "Return": base64::Engine::encode(&base64::engine::general_purpose::STANDARD, ret),
+                "GasUsed": 1000,
+                "EventsRoot": null
+            },
+            "ReturnDec": null,
+            "TipSet": [],
+            "Height": 10
+        }))
+        .unwrap();
+
+        let result = MessageResult::from_lookup(lookup).unwrap();
+        assert!(result.ensure_success().is_ok());
+        let proposal = result.propose_return().unwrap();
+        assert_eq!(proposal.txn_id.0, 42);
+        assert!(!proposal.applied);
+    }
+    #[test]
+    fn test_decode_exec_return() {
+        let created = ExecReturn {
+            id_address: Address::new_id(1234),
+            robust_address: Address::new_actor(b"multisig"),
+        };
+        let result = MessageResult {
+            cid: String::from("bafy2bzacea"),
+            height: 10,
+            exit_code: 0,
+            gas_used: 1000,
+            return_data: to_vec(&created).unwrap(),
+        };
+        let decoded = result.exec_return().unwrap();
+        assert_eq!(decoded.id_address, created.id_address);
+        assert_eq!(decoded.robust_address, created.robust_address);
+    }
+}
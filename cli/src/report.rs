@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use contract_bindings::payment_splitter_native_addr::PaymentSplitterNativeAddr as PaymentSplitter;
+use contract_bindings::payout_factory_native_addr::PayoutFactoryNativeAddr as PayoutFactory;
+use ethers::abi::Address;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::U256;
+use log::info;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tabled::Tabled;
+
+use crate::earnings::payout_month;
+use crate::error::CLIError;
+use crate::index::{index_events, EventIndex};
+use crate::utils::{format_table, format_u256};
+
+/// Month reported for payout contracts missing from the event index.
+const UNINDEXED_MONTH: &str = "unindexed";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+/// Balances of a payout contract deployed by the factory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitterBalance {
+    pub index: usize,
+    pub address: Address,
+    /// Timestamp of the block the payout contract was created in, if indexed.
+    pub created_at: Option<u64>,
+    pub total_shares: U256,
+    pub total_released: U256,
+    pub balance: U256,
+}
+
+/// Totals of the payout contracts created in a month, in FIL.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Tabled)]
+#[serde(rename_all = "PascalCase")]
+pub struct MonthlyLiability {
+    #[tabled(rename = "Month")]
+    pub month: String,
+    #[tabled(rename = "Payout Contracts")]
+    pub payout_contracts: usize,
+    #[tabled(rename = "Deployed")]
+    pub deployed: Decimal,
+    #[tabled(rename = "Claimed")]
+    pub claimed: Decimal,
+    #[tabled(rename = "Unclaimed")]
+    pub unclaimed: Decimal,
+    /// Funds held by the payout contracts, which should match the unclaimed
+    /// amount.
+    #[tabled(rename = "Balance")]
+    pub balance: Decimal,
+}
+
+/// Liabilities of a factory towards the payees of its payout contracts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FactoryReport {
+    pub factory: String,
+    pub months: Vec<MonthlyLiability>,
+    /// `totalReleased` of the factory.
+    pub total_released: Decimal,
+    /// Balance of the factory not yet sent to a payout contract.
+    pub idle_balance: Decimal,
+}
+
+/// Sums the balances of payout contracts per month of creation, in
+/// chronological order, with unindexed contracts last.
+pub fn monthly_liabilities(
+    splitters: &[SplitterBalance],
+) -> Result<Vec<MonthlyLiability>, CLIError> {
+    let mut months: BTreeMap<Option<String>, MonthlyLiability> = BTreeMap::new();
+    for splitter in splitters {
+        let month = splitter.created_at.and_then(payout_month);
+        let totals = months
+            .entry(month.clone())
+            .or_insert_with(|| MonthlyLiability {
+                month: month.unwrap_or_else(|| String::from(UNINDEXED_MONTH)),
+                payout_contracts: 0,
+                deployed: Decimal::ZERO,
+                claimed: Decimal::ZERO,
+                unclaimed: Decimal::ZERO,
+                balance: Decimal::ZERO,
+            });
+        totals.payout_contracts += 1;
+        totals.deployed += format_u256(splitter.total_shares)?;
+        totals.claimed += format_u256(splitter.total_released)?;
+        // FIL sent to a splitter on top of its shares can be released too,
+        // so `totalReleased` may exceed `totalShares`
+        totals.unclaimed += format_u256(
+            splitter
+                .total_shares
+                .saturating_sub(splitter.total_released),
+        )?;
+        totals.balance += format_u256(splitter.balance)?;
+    }
+    // `None` sorts first, move the unindexed contracts after the months
+    let mut liabilities: Vec<MonthlyLiability> = months.into_values().collect();
+    if liabilities
+        .first()
+        .map_or(false, |totals| totals.month == UNINDEXED_MONTH)
+    {
+        liabilities.rotate_left(1);
+    }
+    Ok(liabilities)
+}
+
+/// Reads the shares, released funds and balance of every payout contract of
+/// a factory, with its creation time from the event index.
+pub async fn get_splitter_balances(
+    provider: &Provider<Http>,
+    factory: &PayoutFactory<Provider<Http>>,
+    index: &EventIndex,
+) -> Result<Vec<SplitterBalance>, CLIError> {
+    let client = Arc::new(provider.clone());
+    let payouts = factory.payouts().call().await?;
+
+    let mut splitters = Vec::new();
+    for (splitter_index, address) in payouts.into_iter().enumerate() {
+        let splitter = PaymentSplitter::new(address, client.clone());
+        let created_at = index
+            .splitter(&factory.address(), &address)?
+            .map(|(_, timestamp)| timestamp);
+        splitters.push(SplitterBalance {
+            index: splitter_index,
+            address,
+            created_at,
+            total_shares: splitter.total_shares().call().await?,
+            total_released: splitter.total_released().call().await?,
+            balance: provider.get_balance(address, None).await?,
+        });
+    }
+    Ok(splitters)
+}
+
+fn write_report(
+    report: &FactoryReport,
+    format: ReportFormat,
+    output: &mut dyn Write,
+) -> Result<(), CLIError> {
+    match format {
+        ReportFormat::Table => {
+            writeln!(output, "{}", format_table(report.months.iter()))?;
+        }
+        ReportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(output);
+            for totals in report.months.iter() {
+                writer.serialize(totals)?;
+            }
+            writer.flush()?;
+        }
+        ReportFormat::Json => {
+            serde_json::to_writer_pretty(&mut *output, report)?;
+            writeln!(output)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reports the funds deployed to, claimed from and left unclaimed in the
+/// payout contracts of a factory per month, along with the funds released
+/// by the factory and its idle balance.
+///
+/// The months are read from the event index at `db_path`, which is brought
+/// up to date first. The report is written to `output`, or to stdout.
+pub async fn factory_report(
+    provider: &Provider<Http>,
+    factory_address: &str,
+    db_path: &Path,
+    from_block: u64,
+    format: ReportFormat,
+    output: &Option<PathBuf>,
+) -> Result<FactoryReport, CLIError> {
+    let factory_addr = Address::from_str(factory_address)?;
+    let factory = PayoutFactory::new(factory_addr, Arc::new(provider.clone()));
+
+    index_events(provider, factory_address, db_path, from_block).await?;
+    let index = EventIndex::open(db_path)?;
+
+    let splitters = get_splitter_balances(provider, &factory, &index).await?;
+    for splitter in splitters
+        .iter()
+        .filter(|splitter| splitter.created_at.is_none())
+    {
+        info!(
+            "payout contract {} ({:?}) is not indexed, index from the block the factory was deployed at",
+            splitter.index, splitter.address
+        );
+    }
+    let report = FactoryReport {
+        factory: factory_address.to_string(),
+        months: monthly_liabilities(&splitters)?,
+        total_released: format_u256(factory.total_released().call().await?)?,
+        idle_balance: format_u256(provider.get_balance(factory_addr, None).await?)?,
+    };
+
+    let unclaimed: Decimal = report.months.iter().map(|totals| totals.unclaimed).sum();
+    info!(
+        "\n\n  Factory report for {} \n\n  Payout contracts: {}\n  Unclaimed: {} FIL\n  Released by the factory: {} FIL\n  Idle factory balance: {} FIL\n",
+        factory_address,
+        splitters.len(),
+        unclaimed,
+        report.total_released,
+        report.idle_balance
+    );
+
+    match output {
+        Some(path) => {
+            let mut file = std::fs::File::create(path)?;
+            write_report(&report, format, &mut file)?;
+            info!("Wrote factory report to {:?}", path);
+        }
+        None => write_report(&report, format, &mut std::io::stdout())?,
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{monthly_liabilities, write_report, FactoryReport, ReportFormat, SplitterBalance};
+    use ethers::abi::Address;
+    use ethers::types::U256;
+    use rust_decimal::Decimal;
+
+    fn splitter(
+        index: usize,
+        created_at: Option<u64>,
+        shares: u64,
+        released: u64,
+    ) -> SplitterBalance {
+        let fil = U256::exp10(18);
+        SplitterBalance {
+            index,
+            address: Address::from_low_u64_be(index as u64),
+            created_at,
+            total_shares: fil * shares,
+            total_released: fil * released,
+            balance: fil * shares.saturating_sub(released),
+        }
+    }
+
+    #[test]
+    fn test_monthly_liabilities() {
+        // 2023-04-01T00:00:00Z and 2023-05-01T00:00:00Z
+        let april = 1_680_307_200;
+        let may = 1_682_899_200;
+        let splitters = vec![
+            splitter(0, None, 1, 1),
+            splitter(1, Some(may), 10, 4),
+            splitter(2, Some(april), 5, 5),
+            splitter(3, Some(april + 60), 3, 0),
+        ];
+
+        let liabilities = monthly_liabilities(&splitters).unwrap();
+        let months: Vec<&str> = liabilities.iter().map(|t| t.month.as_str()).collect();
+        assert_eq!(months, vec!["2023-04", "2023-05", "unindexed"]);
+
+        let april = &liabilities[0];
+        assert_eq!(april.payout_contracts, 2);
+        assert_eq!(april.deployed, Decimal::from(8));
+        assert_eq!(april.claimed, Decimal::from(5));
+        assert_eq!(april.unclaimed, Decimal::from(3));
+        assert_eq!(april.balance, april.unclaimed);
+        assert_eq!(liabilities[1].unclaimed, Decimal::from(6));
+        assert_eq!(liabilities[2].payout_contracts, 1);
+
+        assert!(monthly_liabilities(&[]).unwrap().is_empty());
+
+        // extra FIL sent to a splitter was released on top of its shares
+        let liabilities = monthly_liabilities(&[splitter(0, Some(may), 2, 3)]).unwrap();
+        assert_eq!(liabilities[0].claimed, Decimal::from(3));
+        assert_eq!(liabilities[0].unclaimed, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_write_report() {
+        let report = FactoryReport {
+            factory: String::from("0x01"),
+            months: monthly_liabilities(&[splitter(0, Some(1_680_307_200), 2, 1)]).unwrap(),
+            total_released: Decimal::from(1),
+            idle_balance: Decimal::from(7),
+        };
+
+        let mut csv = Vec::new();
+        write_report(&report, ReportFormat::Csv, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("Month,PayoutContracts,Deployed,Claimed,Unclaimed,Balance")
+        );
+        assert!(lines.next().unwrap().starts_with("2023-04,1,"));
+
+        let mut json = Vec::new();
+        write_report(&report, ReportFormat::Json, &mut json).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(json["idleBalance"], "7");
+        assert_eq!(json["months"][0]["Month"], "2023-04");
+    }
+}
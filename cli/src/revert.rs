@@ -0,0 +1,171 @@
+use contract_bindings::payment_splitter_native_addr::{
+    ActorError, ActorNotFound, FailToCallActor, InvalidAddress, InvalidCodec,
+    InvalidResponseLength, NotEnoughBalance, PaymentSplitterNativeAddrErrors,
+};
+use ethers::contract::EthError;
+use ethers::providers::JsonRpcError;
+use ethers::types::U256;
+
+/// `Panic(uint256)`, which failed asserts and arithmetic errors revert with.
+#[derive(Clone, Debug, PartialEq, Eq, EthError)]
+#[etherror(name = "Panic", abi = "Panic(uint256)")]
+struct Panic(U256);
+
+/// Decodes the errors the payout contracts revert with, which include the
+/// errors of the Filecoin actor calls releasing funds.
+///
+/// Each error is matched on its own selector, as the generated `AbiDecode`
+/// of the enum tries every error in turn on the data without one.
+fn decode_splitter_error(data: &[u8]) -> Option<PaymentSplitterNativeAddrErrors> {
+    use PaymentSplitterNativeAddrErrors as Errors;
+    String::decode_with_selector(data)
+        .map(Errors::RevertString)
+        .or_else(|| ActorError::decode_with_selector(data).map(Errors::ActorError))
+        .or_else(|| ActorNotFound::decode_with_selector(data).map(Errors::ActorNotFound))
+        .or_else(|| FailToCallActor::decode_with_selector(data).map(Errors::FailToCallActor))
+        .or_else(|| InvalidAddress::decode_with_selector(data).map(Errors::InvalidAddress))
+        .or_else(|| InvalidCodec::decode_with_selector(data).map(Errors::InvalidCodec))
+        .or_else(|| {
+            InvalidResponseLength::decode_with_selector(data).map(Errors::InvalidResponseLength)
+        })
+        .or_else(|| NotEnoughBalance::decode_with_selector(data).map(Errors::NotEnoughBalance))
+}
+
+fn describe_custom_error<E: EthError + std::fmt::Display>(error: E) -> String {
+    format!("{}({})", E::error_name(), error)
+}
+
+fn describe_splitter_error(error: PaymentSplitterNativeAddrErrors) -> String {
+    use PaymentSplitterNativeAddrErrors as Errors;
+    match error {
+        Errors::RevertString(reason) => reason,
+        Errors::ActorError(error) => describe_custom_error(error),
+        Errors::ActorNotFound(error) => describe_custom_error(error),
+        Errors::FailToCallActor(error) => describe_custom_error(error),
+        Errors::InvalidAddress(error) => describe_custom_error(error),
+        Errors::InvalidCodec(error) => describe_custom_error(error),
+        Errors::InvalidResponseLength(error) => describe_custom_error(error),
+        Errors::NotEnoughBalance(error) => describe_custom_error(error),
+    }
+}
+
+/// Decodes the revert data of a contract call to a human readable reason.
+///
+/// `Error(string)` reverts, raised by `require` in `PayoutFactory` and the
+/// OpenZeppelin `AccessControl`, decode to their message, and the custom
+/// errors of the payout contracts to their name and fields. Data that cannot
+/// be decoded is returned as hex.
+///
+/// ```
+/// # use cli::revert::decode_revert_data;
+/// let data = hex::decode(
+///     "08c379a0\
+///      0000000000000000000000000000000000000000000000000000000000000020\
+///      000000000000000000000000000000000000000000000000000000000000002c\
+///      5061796f7574466163746f72793a20546f6f206d616e7920636f6e7472616374\
+///      7320746f2072656c656173650000000000000000000000000000000000000000",
+/// )
+/// .unwrap();
+/// assert_eq!(
+///     decode_revert_data(&data),
+///     "PayoutFactory: Too many contracts to release"
+/// );
+/// ```
+pub fn decode_revert_data(data: &[u8]) -> String {
+    if data.is_empty() {
+        return String::from("reverted without a reason");
+    }
+    if let Some(error) = decode_splitter_error(data) {
+        return describe_splitter_error(error);
+    }
+    if let Some(Panic(code)) = Panic::decode_with_selector(data) {
+        return format!("panic code {:#x}", code);
+    }
+    format!("0x{}", hex::encode(data))
+}
+
+/// Returns the revert reason of a failed JSON-RPC request, or `None` if the
+/// request did not fail with a revert.
+///
+/// Lotus reports the reason of some reverts in the message of the error
+/// rather than in its data, in which case the message is returned.
+pub fn revert_reason(response: &JsonRpcError) -> Option<String> {
+    let data = response.as_revert_data()?;
+    if data.is_empty() {
+        return Some(response.message.clone());
+    }
+    Some(decode_revert_data(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_revert_data, revert_reason};
+    use contract_bindings::payment_splitter_native_addr::{ActorError, NotEnoughBalance};
+    use ethers::abi::AbiEncode;
+    use ethers::contract::EthError;
+    use ethers::providers::JsonRpcError;
+    use ethers::types::{I256, U256};
+
+    fn error_string(reason: &str) -> Vec<u8> {
+        let mut data = vec![0x08, 0xc3, 0x79, 0xa0];
+        data.extend(reason.to_string().encode());
+        data
+    }
+
+    #[test]
+    fn test_decode_revert_data() {
+        let reason = "AccessControl: account 0x0000000000000000000000000000000000000001 is missing role 0x0000000000000000000000000000000000000000000000000000000000000000";
+        assert_eq!(decode_revert_data(&error_string(reason)), reason);
+
+        let mut panic = vec![0x4e, 0x48, 0x7b, 0x71];
+        panic.extend(U256::from(0x11).encode());
+        assert_eq!(decode_revert_data(&panic), "panic code 0x11");
+
+        // without its selector, the data also decodes as ActorError(int256)
+        let mut not_enough_balance = NotEnoughBalance::selector().to_vec();
+        not_enough_balance.extend((U256::from(1), U256::from(2)).encode());
+        assert_eq!(
+            decode_revert_data(&not_enough_balance),
+            "NotEnoughBalance(1, 2)"
+        );
+        let mut actor_error = ActorError::selector().to_vec();
+        actor_error.extend(I256::from(3).encode());
+        assert_eq!(decode_revert_data(&actor_error), "ActorError(3)");
+
+        assert_eq!(decode_revert_data(&[]), "reverted without a reason");
+        assert_eq!(decode_revert_data(&[0xde, 0xad, 0xbe, 0xef]), "0xdeadbeef");
+    }
+
+    #[test]
+    fn test_revert_reason() {
+        let response = JsonRpcError {
+            code: 3,
+            message: String::from("execution reverted"),
+            data: Some(serde_json::Value::String(format!(
+                "0x{}",
+                hex::encode(error_string("PayoutFactory: Too many contracts to release"))
+            ))),
+        };
+        assert_eq!(
+            revert_reason(&response).unwrap(),
+            "PayoutFactory: Too many contracts to release"
+        );
+
+        let response = JsonRpcError {
+            code: 1,
+            message: String::from("message execution failed: exit 33, revert reason: none"),
+            data: None,
+        };
+        assert_eq!(
+            revert_reason(&response).unwrap(),
+            "message execution failed: exit 33, revert reason: none"
+        );
+
+        let response = JsonRpcError {
+            code: -32000,
+            message: String::from("insufficient funds"),
+            data: None,
+        };
+        assert!(revert_reason(&response).is_none());
+    }
+}
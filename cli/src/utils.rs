@@ -1,14 +1,12 @@
 use std::collections::HashMap;
-use std::error::Error;
 use std::path::PathBuf;
 use std::process::Command;
 
 use chrono::{DateTime, Datelike, Month, NaiveDate, Utc};
 use contract_bindings::shared_types::FilAddress;
-use ethers::abi::AbiDecode;
-use ethers::types::{Eip1559TransactionRequest, U256};
+use ethers::types::{Eip1559TransactionRequest, TxHash, U256};
 
-use csv::{Error as CsvError, Writer};
+use csv::Writer;
 use extras::json::tokenamount;
 use extras::signed_message::ref_fvm::SignedMessage;
 use fevm_utils::filecoin_to_eth_address;
@@ -28,8 +26,6 @@ use libsecp256k1::{PublicKey, SecretKey};
 use rpassword::read_password;
 use serde_json::Value;
 
-use once_cell::sync::Lazy;
-
 use contract_bindings::payout_factory_native_addr::PayoutFactoryNativeAddr as PayoutFactory;
 use ethers::abi::Address;
 use ethers::core::k256::ecdsa::SigningKey;
@@ -37,9 +33,10 @@ use ethers::middleware::SignerMiddleware;
 use ethers::providers::{Http, JsonRpcClient, Middleware, Provider};
 use ethers::signers::Wallet;
 use ethers::types::transaction::eip2718::TypedTransaction;
-use fevm_utils::{check_address_string, get_wallet_signing_provider, send_tx, set_tx_gas};
-use log::{debug, error, info};
-use num_traits::{FromPrimitive, ToPrimitive};
+use fevm_utils::{check_address_string, get_wallet_signing_provider, send_tx};
+use log::{error, info};
+use num_traits::FromPrimitive;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::fs::read_to_string;
 use std::io::{self, Write};
@@ -49,7 +46,13 @@ use tabled::{settings::object::Object, Table, Tabled};
 use url::Url;
 
 const EXPLORER_ADDR: &str = "https://explorer.glif.io/tx";
-const ADMIN_ROLE: [u8; 32] = [0; 32];
+/// Role id of `DEFAULT_ADMIN_ROLE` on the factory.
+pub const ADMIN_ROLE: [u8; 32] = [0; 32];
+/// ID of the Ethereum Address Manager, the namespace of f410 addresses.
+pub const EAM_ACTOR_ID: u64 = 10;
+/// Number of blocks queried per `eth_getLogs` request. Lotus rejects log
+/// queries over larger ranges.
+pub const LOG_QUERY_RANGE: u64 = 2880;
 
 const LOTUS_RPC_URL: &str = "http://127.0.0.1:1234/rpc/v1";
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -91,9 +94,9 @@ pub struct MultiSigTransaction {
 #[serde(rename_all = "PascalCase")]
 pub struct PayoutInformation {
     #[serde(rename = "Total Shares")]
-    pub shares: f64,
-    pub released: f64,
-    pub releasable: f64,
+    pub shares: Decimal,
+    pub released: Decimal,
+    pub releasable: Decimal,
 }
 
 fn display_vector<T: std::fmt::Debug>(v: &Vec<T>) -> String {
@@ -116,6 +119,21 @@ fn display_option<T: std::fmt::Debug>(v: &Option<T>) -> String {
     }
 }
 
+/// Renders rows as a table in the cli's table style.
+pub fn format_table<T: Tabled>(rows: impl IntoIterator<Item = T>) -> Table {
+    let mut table = Table::new(rows);
+    table.with(tabled::settings::Style::modern());
+    table.with(
+        tabled::settings::Modify::new(
+            tabled::settings::object::Rows::new(1..)
+                .not(tabled::settings::object::Columns::first()),
+        )
+        .with(tabled::settings::Alignment::center()),
+    );
+    table.with(tabled::settings::Shadow::new(1));
+    table
+}
+
 fn display_txns(v: &PendingTxns) -> String {
     format!("{:?}", &v.field[..10])
 }
@@ -146,14 +164,28 @@ pub struct MultiSigActor {
     pub state: State,
 }
 
+use crate::admins::resolve_grantee;
 use crate::db::{get_payment_records, PayoutRecords};
+use crate::error::CLIError;
+use crate::gas::gas_policy;
+use crate::journal::{BatchStatus, PayoutJournal};
+use crate::mpool::NonceAllocator;
+use crate::multisig::{describe_pending_transaction, proposal_hash_bytes};
+use crate::plan::{plan_new_payout, plan_propose_payout};
+use crate::receipt::wait_for_message;
+use crate::validate::validate_csv;
+
+/// Number of attoFIL in a FIL.
+pub const ATTO_FIL: u128 = 1_000_000_000_000_000_000;
 
-pub static ATTO_FIL: Lazy<f64> = Lazy::new(|| 10_f64.powf(18.0));
+/// Number of decimal places of a FIL amount.
+pub const FIL_DECIMALS: u32 = 18;
 
 pub const MAX_PAYEES_PER_PAYOUT: usize = 305;
 
-// MaxFee is set to zero when using MpoolPush
-const MAX_FEE: &str = "0";
+/// Maximum number of payout contract indices `releaseSelect` accepts in a
+/// single call.
+pub const MAX_RELEASE_SELECT_INDICES: usize = 12;
 
 const BIP44_PATH: BIP44Path = BIP44Path {
     // The purpose of the 0x8000_0000 is to add the apostrophe(') in a BipPath
@@ -164,39 +196,129 @@ const BIP44_PATH: BIP44Path = BIP44Path {
     index: 0,
 };
 
-#[derive(thiserror::Error, Debug)]
-pub enum CLIError {
-    #[error("contract failed to deploy")]
-    ContractNotDeployed,
-}
-
 #[derive(Deserialize, Debug)]
 #[allow(non_snake_case)]
 struct Payment {
     Recipient: String,
-    FIL: f64,
+    // Kept as a string so that amounts are parsed exactly, see `parse_fil_amount`.
+    FIL: String,
 }
 
 /// calldata is encoding as a byte array of variable length with length encoded by (1, 2, 4, 8 bytes)
-const PARAMS_CBOR_HEADER: [&str; 4] = ["58", "59", "5a", "5b"];
+pub const PARAMS_CBOR_HEADER: [&str; 4] = ["58", "59", "5a", "5b"];
+
+/// Returns the index in `PARAMS_CBOR_HEADER` of the header used to encode
+/// calldata of `len` bytes.
+///
+/// ```
+/// # use cli::utils::cbor_header_index;
+/// assert_eq!(cbor_header_index(255), 0);
+/// assert_eq!(cbor_header_index(0x9c00), 1);
+/// assert_eq!(cbor_header_index(65536), 2);
+/// ```
+pub fn cbor_header_index(len: usize) -> usize {
+    match len as u64 {
+        0..=0xff => 0,
+        0x100..=0xffff => 1,
+        0x1_0000..=0xffff_ffff => 2,
+        _ => 3,
+    }
+}
+
+/// Wraps EVM calldata as the CBOR byte string expected by `InvokeContract`.
+pub fn encode_calldata_params(call_bytes: Vec<u8>) -> Result<Vec<u8>, CLIError> {
+    let header_index = cbor_header_index(call_bytes.len());
+    let len_bytes = (call_bytes.len() as u64).to_be_bytes();
+    let mut params = hex::decode(PARAMS_CBOR_HEADER[header_index])?;
+    // the length is encoded on 1, 2, 4 or 8 bytes
+    params.extend_from_slice(&len_bytes[len_bytes.len() - (1 << header_index)..]);
+    params.extend(call_bytes);
+    Ok(params)
+}
+
+/// Parses a decimal FIL amount exactly.
+///
+/// Amounts with more than 18 decimal places cannot be represented in attoFIL and
+/// are rejected rather than truncated.
+///
+/// ```
+/// # use cli::utils::parse_fil_amount;
+/// assert_eq!(parse_fil_amount("0.000000000000000001").unwrap().to_string(), "0.000000000000000001");
+/// assert!(parse_fil_amount("0.0000000000000000001").is_err());
+/// ```
+pub fn parse_fil_amount(amount: &str) -> Result<Decimal, CLIError> {
+    let value = Decimal::from_str_exact(amount.trim())
+        .map_err(|err| CLIError::InvalidFilAmount(amount.to_string(), err.to_string()))?;
+    if value.normalize().scale() > FIL_DECIMALS {
+        return Err(CLIError::InvalidFilAmount(
+            amount.to_string(),
+            format!("more than {} decimal places", FIL_DECIMALS),
+        ));
+    }
+    Ok(value)
+}
+
+/// Converts an exact FIL amount to attoFIL.
+///
+/// ```
+/// # use cli::utils::{fil_to_atto, parse_fil_amount};
+/// # use ethers::types::U256;
+/// let amount = parse_fil_amount("1.5").unwrap();
+/// assert_eq!(fil_to_atto(&amount).unwrap(), U256::from(1_500_000_000_000_000_000_u128));
+/// ```
+pub fn fil_to_atto(amount: &Decimal) -> Result<U256, CLIError> {
+    let value = amount.normalize();
+    if value.is_sign_negative() && !value.is_zero() {
+        return Err(CLIError::InvalidFilAmount(
+            amount.to_string(),
+            String::from("amount is negative"),
+        ));
+    }
+    if value.scale() > FIL_DECIMALS {
+        return Err(CLIError::InvalidFilAmount(
+            amount.to_string(),
+            format!("more than {} decimal places", FIL_DECIMALS),
+        ));
+    }
+    let mantissa = U256::from(value.mantissa().unsigned_abs());
+    Ok(mantissa * U256::exp10((FIL_DECIMALS - value.scale()) as usize))
+}
+
+/// Formats the address of a payee as a Filecoin address, or as hex if the
+/// bytes are not a valid address.
+pub fn format_fil_address(address: &FilAddress) -> String {
+    match FilecoinAddress::from_bytes(&address.data) {
+        Ok(address) => address.to_string(),
+        Err(_) => format!("0x{}", hex::encode(&address.data)),
+    }
+}
 
-pub fn parse_payouts(payees: &Vec<String>, shares: &Vec<f64>) -> (Vec<FilAddress>, Vec<U256>) {
+/// Converts a FIL amount to a `TokenAmount` for the value of Filecoin messages.
+pub fn fil_to_token_amount(amount: &Decimal) -> Result<TokenAmount, CLIError> {
+    let atto = fil_to_atto(amount)?;
+    Ok(TokenAmount::from_atto(BigInt::from_str(&atto.to_string())?))
+}
+
+pub fn parse_payouts(
+    payees: &Vec<String>,
+    shares: &Vec<Decimal>,
+) -> Result<(Vec<FilAddress>, Vec<U256>), CLIError> {
     let payees = payees
         .iter()
         .map(|payee| {
-            let addr = check_address_string(payee).unwrap();
-            FilAddress {
+            let addr = check_address_string(payee).map_err(CLIError::invalid_input)?;
+            Ok(FilAddress {
                 data: addr.bytes.into(),
-            }
+            })
         })
-        .collect();
+        .collect::<Result<Vec<FilAddress>, CLIError>>()?;
 
-    let shares: Vec<U256> = shares
+    let shares = shares
         .iter()
-        .map(|share| U256::try_from((share * &*ATTO_FIL) as u128).unwrap())
-        .collect();
+        .map(fil_to_atto)
+        .collect::<Result<Vec<U256>, CLIError>>()?;
 
-    (payees, shares)
+    Ok((payees, shares))
 }
 
 /// Parses payouts from a csv file.
@@ -206,19 +328,32 @@ pub fn parse_payouts(payees: &Vec<String>, shares: &Vec<f64>) -> (Vec<FilAddress
 ///    f1...,5
 pub async fn parse_raw_payouts_from_csv(
     payout_csv: &PathBuf,
-) -> Result<(Vec<String>, Vec<f64>), CsvError> {
+) -> Result<(Vec<String>, Vec<Decimal>), CLIError> {
     let mut reader = csv::Reader::from_path(payout_csv)?;
-    let mut shares: Vec<f64> = Vec::new();
+    let mut shares: Vec<Decimal> = Vec::new();
     let mut payees: Vec<String> = Vec::new();
 
     for record in reader.deserialize() {
         let record: Payment = record?;
         payees.push(record.Recipient);
-        shares.push(record.FIL);
+        shares.push(parse_fil_amount(&record.FIL)?);
     }
     Ok((payees, shares))
 }
 
+/// Splits `size` payees into `[start, end)` index ranges of at most `batch_size`.
+///
+/// ```
+/// # use cli::utils::batch_ranges;
+/// assert_eq!(batch_ranges(7, 3), vec![(0, 3), (3, 6), (6, 7)]);
+/// ```
+pub fn batch_ranges(size: usize, batch_size: usize) -> Vec<(usize, usize)> {
+    (0..size)
+        .step_by(batch_size)
+        .map(|start| (start, usize::min(start + batch_size, size)))
+        .collect()
+}
+
 /// Formats a date str to an equivalent Postgres compatible date type using DateTime.
 ///
 /// Usage:
@@ -228,23 +363,26 @@ pub async fn parse_raw_payouts_from_csv(
 /// println!("Formatted Date: {:#?}", formatted_date);
 ///
 /// ```
-pub fn format_date(date: &str) -> Result<DateTime<Utc>, Box<dyn Error>> {
+pub fn format_date(date: &str) -> Result<DateTime<Utc>, CLIError> {
     let date_str = date.to_owned() + "-01";
-    let date = NaiveDate::parse_from_str(date_str.as_str(), "%Y-%m-%d").unwrap();
-    let naive_datetime = date.and_hms_opt(0, 0, 0);
-    let date = match naive_datetime {
-        None => panic!("Error parsing date"),
-        Some(naive_datetime) => DateTime::<Utc>::from_utc(naive_datetime, Utc),
-    };
-    Ok(date)
+    let naive_date = NaiveDate::parse_from_str(date_str.as_str(), "%Y-%m-%d").map_err(|error| {
+        CLIError::InvalidInput(format!(
+            "invalid date '{}', expected YYYY-MM: {}",
+            date, error
+        ))
+    })?;
+    let naive_datetime = naive_date
+        .and_hms_opt(0, 0, 0)
+        .ok_or_else(|| CLIError::InvalidInput(format!("invalid date '{}'", date)))?;
+    Ok(DateTime::<Utc>::from_utc(naive_datetime, Utc))
 }
 
 /// Writes a payout csv to a given path locally.
 fn write_payout_csv(
     path: &PathBuf,
     payees: &Vec<String>,
-    shares: &Vec<f64>,
-) -> Result<(), Box<dyn std::error::Error>> {
+    shares: &Vec<Decimal>,
+) -> Result<(), CLIError> {
     assert_eq!(payees.len(), shares.len());
     let mut csv_writer = Writer::from_path(path)?;
     let headers = &["Recipient", "FIL", "Method", "Params"];
@@ -293,7 +431,7 @@ fn write_payout_csv(
 /// let result = hex_to_ascii("68656c6c6f");
 /// assert!(result.is_err());
 /// ```
-pub fn hex_to_ascii(hex: &str) -> Result<String, Box<dyn std::error::Error>> {
+pub fn hex_to_ascii(hex: &str) -> Result<String, CLIError> {
     let bytes = (0..hex.len())
         .step_by(2)
         .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
@@ -304,19 +442,21 @@ pub fn hex_to_ascii(hex: &str) -> Result<String, Box<dyn std::error::Error>> {
     let json: Value = serde_json::from_str(&ascii_str)?;
     match json.get("PrivateKey") {
         Some(private_key) => Ok(private_key.as_str().unwrap_or_default().to_string()),
-        None => Err("PrivateKey not found in JSON".into()),
+        None => Err(CLIError::Signing(String::from(
+            "PrivateKey not found in JSON",
+        ))),
     }
 }
 
 pub async fn get_signing_method_and_address(
     method: &SigningOptions,
     ledger_account: u32,
-) -> Result<(SignatureMethod, String), Box<dyn Error>> {
+) -> Result<(SignatureMethod, String), CLIError> {
     let signing_method;
 
     match method {
         SigningOptions::Ledger => {
-            let filecoin_ledger_app = get_filecoin_ledger(ledger_account).await;
+            let filecoin_ledger_app = get_filecoin_ledger(ledger_account).await?;
 
             let mut bip_path = BIP44_PATH;
             bip_path.account = ledger_account | 0x8000_0000;
@@ -324,7 +464,7 @@ pub async fn get_signing_method_and_address(
             let address = filecoin_ledger_app
                 .address(&bip_path, false)
                 .await
-                .unwrap()
+                .map_err(CLIError::ledger)?
                 .addr_string;
 
             signing_method = SignatureMethod::LedgerApp(filecoin_ledger_app, bip_path);
@@ -334,16 +474,17 @@ pub async fn get_signing_method_and_address(
             Ok((signing_method, address))
         }
         SigningOptions::Lotus => {
-            let token = get_lotus_signing_token().await.unwrap();
+            let token = get_lotus_signing_token().await?;
 
-            let url: Url = Url::parse(LOTUS_RPC_URL).unwrap();
+            let url: Url = Url::parse(LOTUS_RPC_URL).map_err(CLIError::signing)?;
             let lotus_node_provider =
                 Http::new_with_auth(url, ethers::providers::Authorization::bearer(token.trim()))
-                    .unwrap();
+                    .map_err(CLIError::signing)?;
 
             let address = lotus_node_provider
                 .request::<(), String>("Filecoin.WalletDefaultAddress", ())
-                .await?;
+                .await
+                .map_err(CLIError::signing)?;
 
             signing_method = SignatureMethod::Lotus(lotus_node_provider, address.clone());
 
@@ -354,24 +495,23 @@ pub async fn get_signing_method_and_address(
                 "Insert your private key to sign (it will not be displayed for security reasons): ",
             );
 
-            let _ = io::stdout().flush().unwrap();
-            let mut private_key = read_password().unwrap();
+            io::stdout().flush()?;
+            let mut private_key = read_password()?;
             private_key = String::from(private_key.trim());
 
             if private_key.chars().all(|char| char.is_digit(16)) {
-                private_key = hex_to_ascii(&private_key).unwrap();
+                private_key = hex_to_ascii(&private_key)?;
             }
 
-            let private_key_res = PrivateKey::try_from(private_key);
-
-            let private_key = match private_key_res {
-                Ok(private_key) => private_key,
-                Err(err) => panic!("Error parsing private key: {:?}", err),
-            };
+            let private_key = PrivateKey::try_from(private_key).map_err(|error| {
+                CLIError::Signing(format!("Error parsing private key: {:?}", error))
+            })?;
 
-            let secret_key = SecretKey::parse_slice(&private_key.0)?;
+            let secret_key = SecretKey::parse_slice(&private_key.0).map_err(|error| {
+                CLIError::Signing(format!("Error parsing private key: {:?}", error))
+            })?;
             let public_key = PublicKey::from_secret_key(&secret_key);
-            let address = FilecoinAddress::new_secp256k1(&public_key.serialize().to_vec()).unwrap();
+            let address = FilecoinAddress::new_secp256k1(&public_key.serialize().to_vec())?;
 
             signing_method = SignatureMethod::PrivateKey(private_key);
             info!("Signing with address: {:?}", address.to_string());
@@ -381,21 +521,27 @@ pub async fn get_signing_method_and_address(
     }
 }
 
+/// Proposes the batch of payouts starting at `start_index`. `parsed_payees`
+/// and `parsed_shares` are the payouts as parsed by `parse_payouts`, while
+/// `payees` and `shares` are kept for the failure CSVs.
 pub async fn propose_payout_batch(
     actor_address: &str,
     receiver_address: &str,
     payees: &Vec<String>,
-    shares: &Vec<f64>,
+    shares: &Vec<Decimal>,
+    parsed_payees: &[FilAddress],
+    parsed_shares: &[U256],
     start_index: usize,
     provider: &Provider<Http>,
     rpc_url: &str,
     signature_method: &SignatureMethod,
     signer_address: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+    nonces: &mut NonceAllocator,
+) -> Result<Option<String>, CLIError> {
     let payouts_size = payees.len();
 
     if start_index >= payouts_size {
-        return Ok(());
+        return Ok(None);
     }
 
     let end_index = if start_index + MAX_PAYEES_PER_PAYOUT >= payouts_size {
@@ -410,68 +556,104 @@ pub async fn propose_payout_batch(
 
     let payees = Vec::from(&payees[start_index..end_index]);
     let shares = Vec::from(&shares[start_index..end_index]);
+    let parsed_payees = Vec::from(&parsed_payees[start_index..end_index]);
+    let parsed_shares = Vec::from(&parsed_shares[start_index..end_index]);
 
-    let (parsed_payees, parsed_shares) = parse_payouts(&payees, &shares);
-
-    let factory_addr_eth = filecoin_to_eth_address(&receiver_address, &rpc_url).await?;
+    let nonce = nonces.next();
 
-    let propose_call_data = propose_new_payout_callbytes(
-        Arc::new(provider.clone()),
-        &factory_addr_eth,
+    let message_result = build_propose_payout_message(
+        actor_address,
+        receiver_address,
         parsed_payees,
         parsed_shares,
+        provider,
+        rpc_url,
+        signer_address,
+        nonce,
     )
-    .unwrap();
-
-    let params: ProposeParams = ProposeParams {
-        to: FilecoinAddress::from_str(&receiver_address).unwrap(),
-        // no transfer of value
-        value: TokenAmount::from_atto(BigInt::from_str("0").unwrap()),
-        method: fil_actor_evm::Method::InvokeContract as u64,
-        params: RawBytes::new(propose_call_data),
-    };
-
-    let nonce = get_nonce(&signer_address, provider.clone()).await;
-
-    let mut message = Message {
-        version: 0,
-        to: FilecoinAddress::from_str(&actor_address).unwrap(),
-        from: FilecoinAddress::from_str(&signer_address).unwrap(),
-        sequence: nonce,
-        value: TokenAmount::from_atto(BigInt::from_str("0").unwrap()),
-        gas_limit: 0,
-        gas_fee_cap: TokenAmount::from_atto(BigInt::from_str("0").unwrap()),
-        gas_premium: TokenAmount::from_atto(BigInt::from_str("0").unwrap()),
-        method_num: 2, // Propose is method no 2
-        params: MessageParams::ProposeParams(params).serialize().unwrap(),
+    .await;
+    let mut message = match message_result {
+        Ok(message) => message,
+        Err(error) => {
+            nonces.rollback(nonce)?;
+            return Err(error);
+        }
     };
 
     let signed_message_result = sign_message(provider, signature_method, &mut message).await;
     let signed_message = match signed_message_result {
         Ok(message) => message,
         Err(error) => {
-            write_failure_csv("SignatureFailedPayouts", &payees, &shares);
-            panic!(
-                "Error signing multisig propose message for batch payout at index range {:?} .. {:?}:  {:?}",
-                start_index, end_index, error
-            )
+            nonces.rollback(nonce)?;
+            write_failure_csv("SignatureFailedPayouts", &payees, &shares, &error);
+            error!(
+                "Error signing multisig propose message for batch payout at index range {:?} .. {:?}",
+                start_index, end_index
+            );
+            return Err(error);
         }
     };
 
-    let mpool_push_result: Result<(), Box<dyn Error>> =
-        push_mpool_message(provider, signed_message).await;
+    let mpool_push_result = push_mpool_message(provider, signed_message).await;
 
-    let _ = match mpool_push_result {
-        Ok(mpool_push) => mpool_push,
+    let cid = match mpool_push_result {
+        Ok(cid) => cid,
         Err(error) => {
-            write_failure_csv("MpoolPushFailedPayouts", &payees, &shares);
-            panic!(
-                "MpoolPush error for proposing batch payout at index range {:?} .. {:?}:  {:?}",
-                start_index, end_index, error
-            )
+            nonces.rollback(nonce)?;
+            write_failure_csv("MpoolPushFailedPayouts", &payees, &shares, &error);
+            error!(
+                "MpoolPush error for proposing batch payout at index range {:?} .. {:?}",
+                start_index, end_index
+            );
+            return Err(error);
         }
     };
-    Ok(())
+    Ok(Some(cid))
+}
+
+/// Builds the unsigned multisig `Propose` message for a `payout` call on the
+/// factory at `receiver_address`.
+pub async fn build_propose_payout_message(
+    actor_address: &str,
+    receiver_address: &str,
+    payees: Vec<FilAddress>,
+    shares: Vec<U256>,
+    provider: &Provider<Http>,
+    rpc_url: &str,
+    signer_address: &str,
+    nonce: u64,
+) -> Result<Message, CLIError> {
+    let factory_addr_eth = filecoin_to_eth_address(&receiver_address, &rpc_url)
+        .await
+        .map_err(CLIError::rpc)?;
+
+    let propose_call_data = propose_new_payout_callbytes(
+        Arc::new(provider.clone()),
+        &factory_addr_eth,
+        payees,
+        shares,
+    )?;
+
+    let params: ProposeParams = ProposeParams {
+        to: FilecoinAddress::from_str(&receiver_address)?,
+        // no transfer of value
+        value: TokenAmount::from_atto(BigInt::from_str("0")?),
+        method: fil_actor_evm::Method::InvokeContract as u64,
+        params: RawBytes::new(propose_call_data),
+    };
+
+    Ok(Message {
+        version: 0,
+        to: FilecoinAddress::from_str(&actor_address)?,
+        from: FilecoinAddress::from_str(&signer_address)?,
+        sequence: nonce,
+        value: TokenAmount::from_atto(BigInt::from_str("0")?),
+        gas_limit: 0,
+        gas_fee_cap: TokenAmount::from_atto(BigInt::from_str("0")?),
+        gas_premium: TokenAmount::from_atto(BigInt::from_str("0")?),
+        method_num: 2, // Propose is method no 2
+        params: MessageParams::ProposeParams(params).serialize()?,
+    })
 }
 
 pub async fn cancel_payout(
@@ -480,19 +662,19 @@ pub async fn cancel_payout(
     transaction_id: &str,
     signing_method: &SignatureMethod,
     signing_address: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+    nonces: &mut NonceAllocator,
+) -> Result<String, CLIError> {
     let params: TxnIDParams = TxnIDParams {
-        id: TxnID(i64::from_str(&transaction_id).unwrap()),
+        id: TxnID(i64::from_str(&transaction_id)?),
         proposal_hash: vec![],
     };
 
-    let nonce = get_nonce(&signing_address, provider.clone()).await;
-
     let mut message = Message {
         version: 0,
         to: FilecoinAddress::from_str(&actor_address)?,
         from: FilecoinAddress::from_str(&signing_address)?,
-        sequence: nonce,
+        // set when the message is pushed
+        sequence: 0,
         value: TokenAmount::from_atto(BigInt::from_str("0")?),
         gas_limit: 0,
         gas_fee_cap: TokenAmount::from_atto(BigInt::from_str("0")?),
@@ -501,54 +683,48 @@ pub async fn cancel_payout(
         params: MessageParams::TxnIDParams(params).serialize()?,
     };
 
-    let signed_message: MessageTxAPI = sign_message(provider, signing_method, &mut message).await?;
-
-    push_mpool_message(provider, signed_message).await?;
-    Ok(())
+    sign_and_push_message(provider, signing_method, nonces, &mut message).await
 }
 
+/// Returns the index of every payout contract with funds releasable to
+/// `release_address`, along with the releasable amount.
 pub async fn get_unreleased_payout_contracts(
     factory_address: &str,
     release_address: &str,
     rpc_url: &str,
     provider: &Provider<Http>,
-) -> Result<Vec<U256>, Box<dyn std::error::Error>> {
+) -> Result<Vec<(U256, U256)>, CLIError> {
     let factory_eth_address = filecoin_to_eth_address(factory_address, rpc_url)
         .await
-        .unwrap();
-    let contract_addr = Address::from_str(factory_eth_address.as_str()).unwrap();
+        .map_err(CLIError::rpc)?;
+    let contract_addr = Address::from_str(factory_eth_address.as_str())?;
 
     let client = Arc::new(provider.clone());
     let factory = PayoutFactory::new(contract_addr, client);
 
     let release_addr = FilAddress {
-        data: check_address_string(release_address).unwrap().bytes.into(),
+        data: check_address_string(release_address)
+            .map_err(CLIError::invalid_input)?
+            .bytes
+            .into(),
     };
 
-    let contract_call_result = factory.releasable_per_contract(release_addr).call().await;
-
-    let res = match contract_call_result {
-        Ok(result) => {
-            let contracts = result.0;
-            let releasable_payouts = result.1;
-            assert!(contracts.len() == releasable_payouts.len());
-
-            releasable_payouts
-                .iter()
-                .enumerate()
-                .filter(|&(_, &value)| value != 0.into())
-                .map(|(index, _)| index.into())
-                .collect()
-        }
-        Err(error) => {
-            panic!(
-                "Error extracting payout info for {}: {}",
-                release_address, error
-            )
-        }
-    };
+    let (contracts, releasable_payouts, _) = factory
+        .releasable_per_contract(release_addr)
+        .call()
+        .await
+        .map_err(|error| {
+            error!("Error extracting payout info for {}", release_address);
+            error
+        })?;
+    assert!(contracts.len() == releasable_payouts.len());
 
-    Ok(res)
+    Ok(releasable_payouts
+        .iter()
+        .enumerate()
+        .filter(|&(_, &value)| value != 0.into())
+        .map(|(index, &value)| (index.into(), value))
+        .collect())
 }
 
 pub async fn release_selected_payouts<S: ::ethers::providers::Middleware + 'static>(
@@ -558,24 +734,111 @@ pub async fn release_selected_payouts<S: ::ethers::providers::Middleware + 'stat
     factory_addr: &str,
     addr_to_claim: &str,
     selected_contract_indices: Vec<U256>,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<TxHash, CLIError> {
     let addr = Address::from_str(factory_addr)?;
     let factory = PayoutFactory::new(addr, client.clone());
-    let addr_to_claim = check_address_string(addr_to_claim)?;
+    let addr_to_claim = check_address_string(addr_to_claim).map_err(CLIError::invalid_input)?;
     let claim_addr = FilAddress {
         data: addr_to_claim.bytes.into(),
     };
     let mut claim_tx = factory.release_select(claim_addr, selected_contract_indices);
     let tx = claim_tx.tx.clone();
-    set_tx_gas(
+    gas_policy().set_tx_gas(
         &mut claim_tx.tx,
-        client.estimate_gas(&tx, None).await?,
+        client
+            .estimate_gas(&tx, None)
+            .await
+            .map_err(|error| CLIError::middleware(error, CLIError::GasEstimation))?,
         gas_price,
-    );
+    )?;
 
     info!("estimated claim gas cost {:#?}", claim_tx.tx.gas().unwrap());
 
-    send_tx(&claim_tx.tx, client, retries).await?;
+    let receipt = send_tx(&claim_tx.tx, client, retries)
+        .await
+        .map_err(CLIError::rpc)?;
+    Ok(receipt.transaction_hash)
+}
+
+/// Splits releasable payout contracts, as returned by
+/// `get_unreleased_payout_contracts`, into `releaseSelect` calls of at most
+/// `MAX_RELEASE_SELECT_INDICES` indices, each with the amount it releases.
+pub fn release_select_chunks(releasable: &[(U256, U256)]) -> Vec<(Vec<U256>, U256)> {
+    releasable
+        .chunks(MAX_RELEASE_SELECT_INDICES)
+        .map(|chunk| {
+            let indices = chunk.iter().map(|(index, _)| *index).collect();
+            let amount = chunk
+                .iter()
+                .fold(U256::zero(), |total, (_, value)| total + value);
+            (indices, amount)
+        })
+        .collect()
+}
+
+/// Funds released by the `releaseSelect` calls sent so far for an address.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReleaseProgress {
+    /// Amount released in attoFIL.
+    pub released: U256,
+    /// Hashes of the transactions, or CIDs of the messages, sent.
+    pub transactions: Vec<String>,
+}
+
+/// Releases every releasable payout contract of `addr_to_claim`, sending one
+/// `releaseSelect` transaction per chunk of `MAX_RELEASE_SELECT_INDICES`
+/// contracts. `progress` is updated as each transaction is confirmed.
+pub async fn release_all_selected_payouts<S: ::ethers::providers::Middleware + 'static>(
+    client: Arc<S>,
+    retries: usize,
+    gas_price: U256,
+    factory_addr: &str,
+    addr_to_claim: &str,
+    releasable: &[(U256, U256)],
+    progress: &mut ReleaseProgress,
+) -> Result<(), CLIError> {
+    let chunks = release_select_chunks(releasable);
+    for (i, (indices, amount)) in chunks.iter().enumerate() {
+        let result = release_selected_payouts(
+            client.clone(),
+            retries,
+            gas_price,
+            factory_addr,
+            addr_to_claim,
+            indices.clone(),
+        )
+        .await;
+        match result {
+            Ok(tx_hash) => {
+                progress.released += *amount;
+                progress.transactions.push(format!("{:?}", tx_hash));
+                info!(
+                    "claim {}/{}: released {} FIL from {} payout contracts. check {}/{:?}/",
+                    i + 1,
+                    chunks.len(),
+                    format_fil(*amount),
+                    indices.len(),
+                    EXPLORER_ADDR,
+                    tx_hash
+                );
+            }
+            Err(error) => {
+                error!(
+                    "claim {}/{} failed after releasing {} FIL",
+                    i + 1,
+                    chunks.len(),
+                    format_fil(progress.released)
+                );
+                return Err(error);
+            }
+        }
+    }
+    info!(
+        "released a total of {} FIL to {} from {} payout contracts",
+        format_fil(progress.released),
+        addr_to_claim,
+        releasable.len()
+    );
     Ok(())
 }
 
@@ -586,15 +849,19 @@ pub async fn release_selected_payouts_filecoin_signing(
     selected_contract_indices: Vec<U256>,
     signing_method: &SignatureMethod,
     signing_address: &str,
+    nonces: &mut NonceAllocator,
     rpc_url: &str,
-) -> Result<(), Box<dyn Error>> {
+) -> Result<String, CLIError> {
     let factory_eth_addr = filecoin_to_eth_address(factory_addr, rpc_url)
         .await
-        .unwrap();
+        .map_err(CLIError::rpc)?;
 
-    let addr = Address::from_str(factory_eth_addr.as_str()).unwrap();
+    let addr = Address::from_str(factory_eth_addr.as_str())?;
     let release_addr = FilAddress {
-        data: check_address_string(release_address).unwrap().bytes.into(),
+        data: check_address_string(release_address)
+            .map_err(CLIError::invalid_input)?
+            .bytes
+            .into(),
     };
 
     let client = Arc::new(provider.clone());
@@ -603,26 +870,17 @@ pub async fn release_selected_payouts_filecoin_signing(
     let call_bytes = factory
         .release_select(release_addr, selected_contract_indices)
         .calldata()
-        .unwrap()
+        .ok_or_else(|| CLIError::Other(String::from("failed to encode calldata")))?
         .to_vec();
 
-    let num_bytes = call_bytes.len().to_be_bytes();
-    let num_bytes = num_bytes
-        .iter()
-        .filter(|x| **x != 0)
-        .map(|x| x.clone())
-        .collect::<Vec<u8>>();
-    let mut params = hex::decode(PARAMS_CBOR_HEADER[num_bytes.len() - 1])?;
-    params.extend(num_bytes);
-    params.extend(call_bytes.clone());
-
-    let nonce = get_nonce(&signing_address, provider.clone()).await;
+    let params = encode_calldata_params(call_bytes)?;
 
     let mut message = Message {
         version: 0,
         to: FilecoinAddress::from_str(&factory_addr)?,
         from: FilecoinAddress::from_str(&signing_address)?,
-        sequence: nonce,
+        // set when the message is pushed
+        sequence: 0,
         value: TokenAmount::from_atto(BigInt::from_str("0")?),
         gas_limit: 0,
         gas_fee_cap: TokenAmount::from_atto(BigInt::from_str("0")?),
@@ -630,9 +888,92 @@ pub async fn release_selected_payouts_filecoin_signing(
         method_num: 3844450837, // InvokeContract is method no 3844450837
         params: RawBytes::new(params),
     };
-    let signed_message: MessageTxAPI = sign_message(provider, signing_method, &mut message).await?;
 
-    push_mpool_message(provider, signed_message).await?;
+    sign_and_push_message(provider, signing_method, nonces, &mut message).await
+}
+
+/// Releases every releasable payout contract of `release_address`, pushing
+/// one `releaseSelect` message per chunk of `MAX_RELEASE_SELECT_INDICES`
+/// contracts. With `wait`, each message must execute successfully before the
+/// next one is pushed. `progress` is updated as each message is pushed, or
+/// executed with `wait`.
+pub async fn release_all_selected_payouts_filecoin_signing(
+    provider: &Provider<Http>,
+    factory_addr: &str,
+    release_address: &str,
+    releasable: &[(U256, U256)],
+    signing_method: &SignatureMethod,
+    signing_address: &str,
+    nonces: &mut NonceAllocator,
+    rpc_url: &str,
+    wait: bool,
+    progress: &mut ReleaseProgress,
+) -> Result<(), CLIError> {
+    let chunks = release_select_chunks(releasable);
+    for (i, (indices, amount)) in chunks.iter().enumerate() {
+        let result = async {
+            let cid = release_selected_payouts_filecoin_signing(
+                provider,
+                factory_addr,
+                release_address,
+                indices.clone(),
+                signing_method,
+                signing_address,
+                &mut *nonces,
+                rpc_url,
+            )
+            .await?;
+            if wait {
+                wait_for_message(provider, &cid).await?.ensure_success()?;
+            }
+            Ok::<String, CLIError>(cid)
+        }
+        .await;
+        match result {
+            Ok(cid) => {
+                progress.released += *amount;
+                info!(
+                    "claim {}/{}: {} {} FIL from {} payout contracts in message {}",
+                    i + 1,
+                    chunks.len(),
+                    if wait {
+                        "released"
+                    } else {
+                        "pushed release of"
+                    },
+                    format_fil(*amount),
+                    indices.len(),
+                    cid
+                );
+                progress.transactions.push(cid);
+            }
+            Err(error) => {
+                error!(
+                    "claim {}/{} failed after {} {} FIL",
+                    i + 1,
+                    chunks.len(),
+                    if wait {
+                        "releasing"
+                    } else {
+                        "pushing release of"
+                    },
+                    format_fil(progress.released)
+                );
+                return Err(error);
+            }
+        }
+    }
+    info!(
+        "{} a total of {} FIL to {} from {} payout contracts",
+        if wait {
+            "released"
+        } else {
+            "pushed release of"
+        },
+        format_fil(progress.released),
+        release_address,
+        releasable.len()
+    );
     Ok(())
 }
 
@@ -643,14 +984,17 @@ pub async fn claim_earnings_filecoin_signing(
     signing_method: &SignatureMethod,
     signing_address: &str,
     rpc_url: &str,
-) -> Result<(), Box<dyn Error>> {
+) -> Result<String, CLIError> {
     let factory_eth_addr = filecoin_to_eth_address(factory_addr, rpc_url)
         .await
-        .unwrap();
+        .map_err(CLIError::rpc)?;
 
-    let addr = Address::from_str(factory_eth_addr.as_str()).unwrap();
+    let addr = Address::from_str(factory_eth_addr.as_str())?;
     let release_addr = FilAddress {
-        data: check_address_string(release_address).unwrap().bytes.into(),
+        data: check_address_string(release_address)
+            .map_err(CLIError::invalid_input)?
+            .bytes
+            .into(),
     };
 
     let client = Arc::new(provider.clone());
@@ -659,26 +1003,19 @@ pub async fn claim_earnings_filecoin_signing(
     let call_bytes = factory
         .release_all(release_addr, U256::from(0))
         .calldata()
-        .unwrap()
+        .ok_or_else(|| CLIError::Other(String::from("failed to encode calldata")))?
         .to_vec();
 
-    let num_bytes = call_bytes.len().to_be_bytes();
-    let num_bytes = num_bytes
-        .iter()
-        .filter(|x| **x != 0)
-        .map(|x| x.clone())
-        .collect::<Vec<u8>>();
-    let mut params = hex::decode(PARAMS_CBOR_HEADER[num_bytes.len() - 1])?;
-    params.extend(num_bytes);
-    params.extend(call_bytes.clone());
+    let params = encode_calldata_params(call_bytes)?;
 
-    let nonce = get_nonce(&signing_address, provider.clone()).await;
+    let mut nonces = NonceAllocator::new(provider, signing_address).await?;
 
     let mut message = Message {
         version: 0,
         to: FilecoinAddress::from_str(&factory_addr)?,
         from: FilecoinAddress::from_str(&signing_address)?,
-        sequence: nonce,
+        // set when the message is pushed
+        sequence: 0,
         value: TokenAmount::from_atto(BigInt::from_str("0")?),
         gas_limit: 0,
         gas_fee_cap: TokenAmount::from_atto(BigInt::from_str("0")?),
@@ -687,31 +1024,31 @@ pub async fn claim_earnings_filecoin_signing(
         params: RawBytes::new(params),
     };
 
-    let signed_message: MessageTxAPI = sign_message(provider, signing_method, &mut message).await?;
-
-    push_mpool_message(provider, signed_message).await?;
-    Ok(())
+    sign_and_push_message(provider, signing_method, &mut nonces, &mut message).await
 }
 
+/// Approves a pending transaction of a multisig. The approval carries the
+/// proposal hash of `transaction`, so the actor rejects it if the pending
+/// transaction with that id is a different proposal.
 pub async fn approve_payout(
     actor_address: &str,
     provider: &Provider<Http>,
     signing_method: &SignatureMethod,
     signing_address: &str,
-    transaction_id: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+    transaction: &MultiSigTransaction,
+    nonces: &mut NonceAllocator,
+) -> Result<String, CLIError> {
     let params: TxnIDParams = TxnIDParams {
-        id: TxnID(i64::from_str(&transaction_id).unwrap()),
-        proposal_hash: vec![],
+        id: TxnID(transaction.id as i64),
+        proposal_hash: proposal_hash_bytes(transaction)?,
     };
 
-    let nonce = get_nonce(&signing_address, provider.clone()).await;
-
     let mut message = Message {
         version: 0,
         to: FilecoinAddress::from_str(&actor_address)?,
         from: FilecoinAddress::from_str(&signing_address)?,
-        sequence: nonce,
+        // set when the message is pushed
+        sequence: 0,
         value: TokenAmount::from_atto(BigInt::from_str("0")?),
         gas_limit: 0,
         gas_fee_cap: TokenAmount::from_atto(BigInt::from_str("0")?),
@@ -720,10 +1057,7 @@ pub async fn approve_payout(
         params: MessageParams::TxnIDParams(params).serialize()?,
     };
 
-    let signed_message: MessageTxAPI = sign_message(provider, signing_method, &mut message).await?;
-
-    push_mpool_message(provider, signed_message).await?;
-    Ok(())
+    sign_and_push_message(provider, signing_method, nonces, &mut message).await
 }
 
 pub enum SignatureMethod {
@@ -743,20 +1077,31 @@ pub async fn sign_message(
     provider: &Provider<Http>,
     signature_method: &SignatureMethod,
     message: &mut Message,
-) -> Result<MessageTxAPI, Box<dyn std::error::Error>> {
-    let gas_info = get_gas_info(message.clone(), provider.clone(), MAX_FEE).await;
+) -> Result<MessageTxAPI, CLIError> {
+    let gas_info = get_gas_info(message.clone(), provider.clone()).await?;
 
     message.gas_limit = gas_info.gas_limit;
     message.gas_fee_cap = gas_info.gas_fee_cap;
     message.gas_premium = gas_info.gas_premium;
 
-    let message_bytes = to_vec(&message).unwrap();
+    sign_message_with_gas(signature_method, message).await
+}
+
+/// Signs a message whose gas fields are already set.
+pub async fn sign_message_with_gas(
+    signature_method: &SignatureMethod,
+    message: &Message,
+) -> Result<MessageTxAPI, CLIError> {
+    let message_bytes = to_vec(&message).map_err(CLIError::signing)?;
 
     let signed_message: MessageTxAPI;
 
     match signature_method {
         SignatureMethod::LedgerApp(ledger_app, bip_path) => {
-            let signature = ledger_app.sign(bip_path, &message_bytes).await.unwrap();
+            let signature = ledger_app
+                .sign(bip_path, &message_bytes)
+                .await
+                .map_err(CLIError::ledger)?;
             let recovery_id = signature.v;
             let mut sig = signature.sig.to_vec();
             sig.push(recovery_id);
@@ -769,8 +1114,7 @@ pub async fn sign_message(
             });
         }
         SignatureMethod::PrivateKey(private_key) => {
-            signed_message =
-                MessageTxAPI::SignedMessage(transaction_sign(&message, &private_key).unwrap());
+            signed_message = MessageTxAPI::SignedMessage(transaction_sign(&message, &private_key)?);
         }
         SignatureMethod::Lotus(provider, signer_address) => {
             let message_tx: MessageTxAPI = MessageTxAPI::Message(message.clone());
@@ -779,7 +1123,8 @@ pub async fn sign_message(
                     "Filecoin.WalletSignMessage",
                     (signer_address, message_tx),
                 )
-                .await?;
+                .await
+                .map_err(CLIError::signing)?;
         }
     }
     Ok(signed_message)
@@ -787,17 +1132,25 @@ pub async fn sign_message(
 
 /// Generates a signing token from a locus local node. The token is used to sign
 /// messages using the lotus node.
-pub async fn get_lotus_signing_token() -> Result<String, Box<dyn Error>> {
+pub async fn get_lotus_signing_token() -> Result<String, CLIError> {
     let output = Command::new("lotus")
         .arg("auth")
         .arg("create-token")
         .arg("--perm")
         .arg("sign")
         .output()
-        .expect("Failed to extract signing token from lotus");
+        .map_err(|error| {
+            CLIError::Signing(format!(
+                "Failed to extract signing token from lotus: {}",
+                error
+            ))
+        })?;
 
     if !output.status.success() {
-        panic!("Failed to extract signing token from lotus");
+        return Err(CLIError::Signing(format!(
+            "Failed to extract signing token from lotus: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
     }
 
     let token = String::from_utf8_lossy(&output.stdout).to_string();
@@ -805,22 +1158,42 @@ pub async fn get_lotus_signing_token() -> Result<String, Box<dyn Error>> {
     Ok(token)
 }
 
+/// Signs `message` with the next nonce of `nonces` and pushes it to the mpool,
+/// returning its CID. The nonce is rolled back if the message is not pushed.
+pub async fn sign_and_push_message(
+    provider: &Provider<Http>,
+    signature_method: &SignatureMethod,
+    nonces: &mut NonceAllocator,
+    message: &mut Message,
+) -> Result<String, CLIError> {
+    message.sequence = nonces.next();
+    let pushed = match sign_message(provider, signature_method, message).await {
+        Ok(signed_message) => push_mpool_message(provider, signed_message).await,
+        Err(error) => Err(error),
+    };
+    if pushed.is_err() {
+        nonces.rollback(message.sequence)?;
+    }
+    pushed
+}
+
+/// Pushes a signed message to the mpool and returns its CID.
 pub async fn push_mpool_message(
     provider: &Provider<Http>,
     signed_message: MessageTxAPI,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let result: Value = provider
-        .request::<[MessageTxAPI; 1], Value>("Filecoin.MpoolPush", [signed_message])
+) -> Result<String, CLIError> {
+    let result: TransactionDetails = provider
+        .request::<[MessageTxAPI; 1], TransactionDetails>("Filecoin.MpoolPush", [signed_message])
         .await?;
 
-    info!("{:#?}", result);
-    Ok(())
+    info!("pushed message {}", result.field);
+    Ok(result.field)
 }
 
 pub async fn get_pending_transaction_multisig(
     provider: &Provider<Http>,
     actor_id: &str,
-) -> Result<Vec<MultiSigTransaction>, Box<dyn std::error::Error>> {
+) -> Result<Vec<MultiSigTransaction>, CLIError> {
     let params: (&str, ()) = (actor_id, ());
     let result: Value = provider
         .request::<(&str, ()), Value>("Filecoin.MsigGetPending", params)
@@ -831,23 +1204,43 @@ pub async fn get_pending_transaction_multisig(
     Ok(result)
 }
 
-pub async fn inspect_earnings(provider: &Provider<Http>, address: &str, factory_address: &str) {
-    let contract_addr = Address::from_str(factory_address).unwrap();
+/// Returns the pending transaction `transaction_id` of a multisig.
+pub async fn get_pending_transaction(
+    provider: &Provider<Http>,
+    actor_id: &str,
+    transaction_id: &str,
+) -> Result<MultiSigTransaction, CLIError> {
+    let id = u64::from_str(transaction_id)?;
+    get_pending_transaction_multisig(provider, actor_id)
+        .await?
+        .into_iter()
+        .find(|transaction| transaction.id == id)
+        .ok_or_else(|| {
+            CLIError::InvalidInput(format!("transaction {} is not pending on {}", id, actor_id))
+        })
+}
+
+pub async fn inspect_earnings(
+    provider: &Provider<Http>,
+    address: &str,
+    factory_address: &str,
+) -> Result<(), CLIError> {
+    let contract_addr = Address::from_str(factory_address)?;
 
     let client = Arc::new(provider.clone());
     let contract = PayoutFactory::new(contract_addr, client);
 
-    let address_data = check_address_string(address).unwrap();
+    let address_data = check_address_string(address).map_err(CLIError::invalid_input)?;
 
     let fil_addr = FilAddress {
         data: address_data.bytes.into(),
     };
 
-    let releasable = contract.releasable(fil_addr.clone()).call().await.unwrap();
-    let releasable = format_u256(releasable);
+    let releasable = contract.releasable(fil_addr.clone()).call().await?;
+    let releasable = format_u256(releasable)?;
 
-    let released = contract.released(fil_addr.clone()).await.unwrap();
-    let released = format_u256(released);
+    let released = contract.released(fil_addr.clone()).await?;
+    let released = format_u256(released)?;
 
     let shares = releasable + released;
 
@@ -857,16 +1250,7 @@ pub async fn inspect_earnings(provider: &Provider<Http>, address: &str, factory_
         released,
     };
 
-    let mut table = Table::new(vec![payout_info.clone()].iter());
-    table.with(tabled::settings::Style::modern());
-    table.with(
-        tabled::settings::Modify::new(
-            tabled::settings::object::Rows::new(1..)
-                .not(tabled::settings::object::Columns::first()),
-        )
-        .with(tabled::settings::Alignment::center()),
-    );
-    table.with(tabled::settings::Shadow::new(1));
+    let table = format_table(vec![payout_info]);
 
     let string = format!(
         "\n\n  Inspecting Earnings for address: {} \n\n{}",
@@ -875,12 +1259,13 @@ pub async fn inspect_earnings(provider: &Provider<Http>, address: &str, factory_
     );
 
     info!("{}", string);
+    Ok(())
 }
 
 pub async fn inspect_multisig(
     provider: &Provider<Http>,
     actor_id: &str,
-) -> Result<MultiSigActor, Box<dyn std::error::Error>> {
+) -> Result<MultiSigActor, CLIError> {
     let params: (&str, ()) = (actor_id, ());
     let result: Value = provider
         .request::<(&str, ()), Value>("Filecoin.StateReadState", params)
@@ -890,16 +1275,7 @@ pub async fn inspect_multisig(
 
     let pending_tx = get_pending_transaction_multisig(provider, actor_id).await?;
 
-    let mut table = Table::new(vec![result.clone().state].iter());
-    table.with(tabled::settings::Style::modern());
-    table.with(
-        tabled::settings::Modify::new(
-            tabled::settings::object::Rows::new(1..)
-                .not(tabled::settings::object::Columns::first()),
-        )
-        .with(tabled::settings::Alignment::center()),
-    );
-    table.with(tabled::settings::Shadow::new(1));
+    let table = format_table(vec![result.state.clone()]);
 
     let string = format!(
         "\n\n  MultiSig {} with balance {} \n\n{}",
@@ -911,41 +1287,14 @@ pub async fn inspect_multisig(
     info!("{}", string);
 
     for tx in pending_tx.iter() {
-        let mut table = Table::new(vec![tx.clone()].iter());
-        table.with(tabled::settings::Style::modern());
-        table.with(
-            tabled::settings::Modify::new(
-                tabled::settings::object::Rows::new(1..)
-                    .not(tabled::settings::object::Columns::first()),
-            )
-            .with(tabled::settings::Alignment::center()),
-        );
-        table.with(tabled::settings::Shadow::new(1));
-
+        let fields = describe_pending_transaction(actor_id, tx, result.state.threshold)?;
         let string = format!(
             "\n\n  Pending Transaction {} \n\n{}",
             tx.id,
-            table.to_string()
+            format_table(fields)
         );
 
         info!("{}", string);
-
-        if let Some(params) = &tx.params {
-            let params: String =
-                RawBytes::from(base64::decode(params.as_bytes())?).deserialize()?;
-            let params =
-                contract_bindings::payout_factory_native_addr::PayoutFactoryNativeAddrCalls::decode(
-                    &params.as_bytes(),
-                );
-            match params {
-                Ok(params) => {
-                    debug!("human readable params {:#?}", params);
-                }
-                Err(_) => {
-                    error!("could not parse params");
-                }
-            }
-        }
     }
 
     Ok(result)
@@ -958,24 +1307,29 @@ pub async fn claim_earnings<S: ::ethers::providers::Middleware + 'static>(
     offset: U256,
     factory_addr: &str,
     addr_to_claim: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), CLIError> {
     let addr = Address::from_str(factory_addr)?;
     let factory = PayoutFactory::new(addr, client.clone());
-    let addr_to_claim = check_address_string(addr_to_claim)?;
+    let addr_to_claim = check_address_string(addr_to_claim).map_err(CLIError::invalid_input)?;
     let claim_addr = FilAddress {
         data: addr_to_claim.bytes.into(),
     };
     let mut claim_tx = factory.release_all(claim_addr, offset);
     let tx = claim_tx.tx.clone();
-    set_tx_gas(
+    gas_policy().set_tx_gas(
         &mut claim_tx.tx,
-        client.estimate_gas(&tx, None).await?,
+        client
+            .estimate_gas(&tx, None)
+            .await
+            .map_err(|error| CLIError::middleware(error, CLIError::GasEstimation))?,
         gas_price,
-    );
+    )?;
 
     info!("estimated claim gas cost {:#?}", claim_tx.tx.gas().unwrap());
 
-    send_tx(&claim_tx.tx, client, retries).await?;
+    send_tx(&claim_tx.tx, client, retries)
+        .await
+        .map_err(CLIError::rpc)?;
     Ok(())
 }
 
@@ -986,25 +1340,27 @@ pub async fn grant_admin<S: ::ethers::providers::Middleware + 'static>(
     factory_addr: &str,
     address_to_grant: &str,
     rpc_url: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), CLIError> {
     let addr = Address::from_str(factory_addr)?;
     let factory: PayoutFactory<_> = PayoutFactory::new(addr, client.clone());
-    let address_to_grant = filecoin_to_eth_address(address_to_grant, rpc_url)
-        .await
-        .unwrap();
-    let address_to_grant = Address::from_str(address_to_grant.as_str())?;
+    let address_to_grant = resolve_grantee(client.provider(), address_to_grant, rpc_url).await?;
 
     let mut claim_tx = factory.grant_role(ADMIN_ROLE.into(), address_to_grant);
     let tx = claim_tx.tx.clone();
-    set_tx_gas(
+    gas_policy().set_tx_gas(
         &mut claim_tx.tx,
-        client.estimate_gas(&tx, None).await?,
+        client
+            .estimate_gas(&tx, None)
+            .await
+            .map_err(|error| CLIError::middleware(error, CLIError::GasEstimation))?,
         gas_price,
-    );
+    )?;
 
     info!("estimated grant gas cost {:#?}", claim_tx.tx.gas().unwrap());
 
-    let receipt_result = send_tx(&claim_tx.tx, client, retries).await?;
+    let receipt_result = send_tx(&claim_tx.tx, client, retries)
+        .await
+        .map_err(CLIError::rpc)?;
     info!(
         "admin granted successfully to '{}'. check {}/{}/",
         address_to_grant, EXPLORER_ADDR, receipt_result.transaction_hash,
@@ -1020,25 +1376,30 @@ pub async fn revoke_admin<S: ::ethers::providers::Middleware + 'static>(
     factory_addr: &str,
     address_to_revoke: &str,
     rpc_url: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), CLIError> {
     let addr = Address::from_str(factory_addr)?;
     let factory: PayoutFactory<_> = PayoutFactory::new(addr, client.clone());
     let address_to_revoke = filecoin_to_eth_address(address_to_revoke, rpc_url)
         .await
-        .unwrap();
+        .map_err(CLIError::rpc)?;
     let address_to_revoke = Address::from_str(address_to_revoke.as_str())?;
 
     let mut claim_tx = factory.revoke_role(ADMIN_ROLE.into(), address_to_revoke);
     let tx = claim_tx.tx.clone();
-    set_tx_gas(
+    gas_policy().set_tx_gas(
         &mut claim_tx.tx,
-        client.estimate_gas(&tx, None).await?,
+        client
+            .estimate_gas(&tx, None)
+            .await
+            .map_err(|error| CLIError::middleware(error, CLIError::GasEstimation))?,
         gas_price,
-    );
+    )?;
 
     info!("estimated grant gas cost {:#?}", claim_tx.tx.gas().unwrap());
 
-    let receipt_result = send_tx(&claim_tx.tx, client, retries).await?;
+    let receipt_result = send_tx(&claim_tx.tx, client, retries)
+        .await
+        .map_err(CLIError::rpc)?;
     info!(
         "admin revoked successfully from '{}'. check {}/{}/",
         address_to_revoke, EXPLORER_ADDR, receipt_result.transaction_hash,
@@ -1057,14 +1418,35 @@ pub async fn propose_payout(
     rpc_url: &str,
     signature_method: SignatureMethod,
     signer_address: &str,
-) -> Result<(), Box<dyn Error>> {
-    let (payees, shares) = get_payout_data(db_deploy, &payout_csv, date).await.unwrap();
+    dry_run: bool,
+    plan_file: &Option<PathBuf>,
+) -> Result<Vec<String>, CLIError> {
+    let (payees, shares) = get_payout_data(db_deploy, &payout_csv, date).await?;
+
+    if dry_run {
+        let plan = plan_propose_payout(
+            actor_address,
+            receiver_address,
+            &payees,
+            &shares,
+            provider,
+            rpc_url,
+            signer_address,
+        )
+        .await?;
+        plan.report(plan_file)?;
+        return Ok(vec![]);
+    }
 
-    let total_sum = shares.clone().iter().fold(0_f64, |acc, x| acc + x);
+    let total_sum: Decimal = shares.iter().sum();
 
     info!("Total Sum from Payouts: {:?}", total_sum);
     info!("Total Payee Count: {:?}", payees.len());
 
+    // every share is converted before the first batch is proposed, such that
+    // an invalid amount cannot leave the payout partially proposed
+    let (parsed_payees, parsed_shares) = parse_payouts(&payees, &shares)?;
+
     let payout_size: i32 = payees.len() as i32;
     let batches = if payout_size % (MAX_PAYEES_PER_PAYOUT as i32) == 0 {
         payout_size / (MAX_PAYEES_PER_PAYOUT as i32)
@@ -1073,59 +1455,94 @@ pub async fn propose_payout(
     };
 
     info!("Proposing Payouts in {:?} batch deployments \n ", batches);
+    let mut nonces = NonceAllocator::new(provider, signer_address).await?;
+    let mut cids = Vec::new();
     for i in 0..(batches as usize) {
         let start_index = i * MAX_PAYEES_PER_PAYOUT;
         let propose_result = propose_payout_batch(
             actor_address,
             receiver_address,
-            &payees.clone(),
-            &shares.clone(),
+            &payees,
+            &shares,
+            &parsed_payees,
+            &parsed_shares,
             start_index,
             provider,
             rpc_url,
             &signature_method,
             signer_address,
+            &mut nonces,
         )
         .await;
 
-        let _ = match propose_result {
-            Ok(payout) => payout,
-            Err(error) => panic!(
-                "Error proposing batch payout at start index range {:?}:  {:?}",
-                start_index, error
-            ),
+        match propose_result {
+            Ok(cid) => cids.extend(cid),
+            Err(error) => {
+                error!(
+                    "Error proposing batch payout at start index range {:?}. Messages already pushed: {:?}",
+                    start_index, cids
+                );
+                return Err(error);
+            }
         };
     }
-    Ok(())
+    Ok(cids)
+}
+
+/// Why the payouts of a `*FailedPayouts` CSV failed, written next to it as
+/// JSON.
+#[derive(Debug, Serialize)]
+struct FailedPayoutsMetadata {
+    error: String,
+    /// Decoded reason, when the payout call reverted.
+    revert_reason: Option<String>,
 }
 
-fn write_failure_csv(filename: &str, payees: &Vec<String>, shares: &Vec<f64>) {
+fn write_failure_csv(
+    filename: &str,
+    payees: &Vec<String>,
+    shares: &Vec<Decimal>,
+    failure: &CLIError,
+) {
     let date = chrono::offset::Utc::now().to_string();
     let file_path = PathBuf::from(&format!("./{}{}", date, filename));
-    write_payout_csv(&file_path, &payees, &shares).unwrap();
+    if let Err(error) = write_payout_csv(&file_path, &payees, &shares) {
+        error!(
+            "could not write failed payouts to {:?}: {}",
+            file_path, error
+        );
+    }
+
+    let metadata = FailedPayoutsMetadata {
+        error: failure.to_string(),
+        revert_reason: failure.revert_reason().map(String::from),
+    };
+    let metadata_path = PathBuf::from(&format!("./{}{}.json", date, filename));
+    let written = serde_json::to_string_pretty(&metadata)
+        .map_err(CLIError::from)
+        .and_then(|json| Ok(std::fs::write(&metadata_path, json)?));
+    if let Err(error) = written {
+        error!(
+            "could not write failure metadata to {:?}: {}",
+            metadata_path, error
+        );
+    }
 }
 
-// Deploys a PaymentSplitter batch of node operator payouts.
+// Deploys a PaymentSplitter batch of node operator payouts and records its
+// progress in the payout journal.
 pub async fn deploy_payout_batch<S: Middleware + 'static>(
-    start_index: usize,
+    batch_index: usize,
+    journal: &mut PayoutJournal,
     payees: &Vec<String>,
-    shares: &Vec<f64>,
+    shares: &Vec<Decimal>,
     factory_contract: PayoutFactory<S>,
     client: Arc<S>,
     gas_price: U256,
     retries: usize,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let payouts_size = payees.len();
-
-    if start_index >= payouts_size {
-        return Ok(());
-    }
-
-    let end_index = if start_index + MAX_PAYEES_PER_PAYOUT >= payouts_size {
-        payouts_size
-    } else {
-        start_index + MAX_PAYEES_PER_PAYOUT
-    };
+) -> Result<(), CLIError> {
+    let start_index = journal.batches[batch_index].start_index;
+    let end_index = journal.batches[batch_index].end_index;
     info!(
         "Deploying payouts with index range {:?} ... {:?}",
         start_index, end_index
@@ -1134,7 +1551,7 @@ pub async fn deploy_payout_batch<S: Middleware + 'static>(
     let payees = Vec::from(&payees[start_index..end_index]);
     let shares = Vec::from(&shares[start_index..end_index]);
 
-    let (parsed_payees, parsed_shares) = parse_payouts(&payees, &shares);
+    let (parsed_payees, parsed_shares) = parse_payouts(&payees, &shares)?;
 
     let total_sum = parsed_shares
         .clone()
@@ -1148,32 +1565,69 @@ pub async fn deploy_payout_batch<S: Middleware + 'static>(
     let gas_estimate = match gas_estimate_result {
         Ok(gas) => gas,
         Err(error) => {
-            write_failure_csv("GasEstimateFailedPayouts", &payees, &shares);
-            panic!(
-                "Error estimating gas for batch payout at index range {:?} .. {:?}:  {:?}",
-                start_index, end_index, error
+            let error = CLIError::middleware(error, CLIError::GasEstimation);
+            error!(
+                "Error estimating gas for batch payout at index range {:?} .. {:?}",
+                start_index, end_index
             );
+            write_failure_csv("GasEstimateFailedPayouts", &payees, &shares, &error);
+            journal.record(batch_index, BatchStatus::Failed, None)?;
+            return Err(error);
         }
     };
-    set_tx_gas(&mut payout_tx.tx, gas_estimate, gas_price);
+    gas_policy().set_tx_gas(&mut payout_tx.tx, gas_estimate, gas_price)?;
 
     info!(
         "Estimated batch payout gas cost {:#?}",
         payout_tx.tx.gas().unwrap()
     );
 
-    let receipt_result = send_tx(&payout_tx.tx, client, retries).await;
-    let receipt = match receipt_result {
-        Ok(receipt) => receipt,
+    let pending_tx = match client.send_transaction(payout_tx.tx.clone(), None).await {
+        Ok(pending_tx) => pending_tx,
         Err(error) => {
-            write_failure_csv("ReceiptFailedPayouts", &payees, &shares);
-            panic!(
-                "Error deploying batch payout at index range {:?} .. {:?}:  {:?}",
+            let error = CLIError::middleware(error, CLIError::Rpc);
+            error!(
+                "Error sending batch payout at index range {:?} .. {:?}",
+                start_index, end_index
+            );
+            write_failure_csv("SendFailedPayouts", &payees, &shares, &error);
+            journal.record(batch_index, BatchStatus::Failed, None)?;
+            return Err(error);
+        }
+    };
+
+    // The hash is journaled before waiting on the receipt, such that a resumed
+    // run can look the transaction up even if this process dies while waiting.
+    let tx_hash = pending_tx.tx_hash();
+    journal.record(batch_index, BatchStatus::Submitted, Some(tx_hash))?;
+
+    let receipt = match pending_tx.retries(retries).await {
+        Ok(Some(receipt)) if receipt.status == Some(1.into()) => receipt,
+        Ok(receipt) => {
+            let message = format!(
+                "Batch payout at index range {:?} .. {:?} was not confirmed, TxId: {:?}",
+                start_index, end_index, tx_hash
+            );
+            let (error, status) = match receipt {
+                Some(_) => (CLIError::ContractRevert(message), BatchStatus::Failed),
+                None => (CLIError::Rpc(message), BatchStatus::Submitted),
+            };
+            write_failure_csv("ReceiptFailedPayouts", &payees, &shares, &error);
+            journal.record(batch_index, status, None)?;
+            return Err(error);
+        }
+        Err(error) => {
+            let error = CLIError::Rpc(format!(
+                "Error deploying batch payout at index range {:?} .. {:?}: {}",
                 start_index, end_index, error
-            )
+            ));
+            write_failure_csv("ReceiptFailedPayouts", &payees, &shares, &error);
+            return Err(error);
         }
     };
 
+    journal.record(batch_index, BatchStatus::Confirmed, None)?;
+
     info!(
         "Batch Deployment Successful, TxId: {:?} \n",
         receipt.transaction_hash
@@ -1181,6 +1635,57 @@ pub async fn deploy_payout_batch<S: Middleware + 'static>(
     Ok(())
 }
 
+/// Checks the chain for the outcome of a batch that a previous run journaled as
+/// `Submitted`, and updates the journal accordingly.
+///
+/// Errors if the transaction is still pending, as re-sending it would risk
+/// paying the batch twice.
+async fn reconcile_submitted_batch<S: Middleware + 'static>(
+    batch_index: usize,
+    journal: &mut PayoutJournal,
+    client: Arc<S>,
+) -> Result<(), CLIError> {
+    let batch = journal.batches[batch_index].clone();
+    let tx_hash = match batch.tx_hash {
+        Some(tx_hash) => tx_hash,
+        None => return journal.record(batch_index, BatchStatus::Failed, None),
+    };
+
+    match client
+        .get_transaction_receipt(tx_hash)
+        .await
+        .map_err(CLIError::rpc)?
+    {
+        Some(receipt) if receipt.status == Some(1.into()) => {
+            info!(
+                "Batch at index range {:?} .. {:?} already confirmed on chain, TxId: {:?}",
+                batch.start_index, batch.end_index, tx_hash
+            );
+            journal.record(batch_index, BatchStatus::Confirmed, None)
+        }
+        Some(_) => journal.record(batch_index, BatchStatus::Failed, None),
+        None => {
+            if client
+                .get_transaction(tx_hash)
+                .await
+                .map_err(CLIError::rpc)?
+                .is_some()
+            {
+                return Err(CLIError::Other(format!(
+                    "Batch at index range {:?} .. {:?} is still pending (TxId: {:?}), wait for it before resuming",
+                    batch.start_index, batch.end_index, tx_hash
+                )));
+            }
+            // the transaction was dropped and never made it on chain
+            journal.record(batch_index, BatchStatus::Failed, None)
+        }
+    }
+}
+
+/// Deploys a payout in batches of `MAX_PAYEES_PER_PAYOUT`.
+///
+/// Every run is journaled to a JSON file. Passing a journal as `resume` skips
+/// the batches it records as confirmed on chain and only retries the others.
 pub async fn new_payout<S: Middleware + 'static>(
     client: Arc<S>,
     retries: usize,
@@ -1189,31 +1694,73 @@ pub async fn new_payout<S: Middleware + 'static>(
     payout_csv: &Option<PathBuf>,
     db_deploy: &bool,
     date: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+    resume: &Option<PathBuf>,
+    dry_run: bool,
+    plan_file: &Option<PathBuf>,
+) -> Result<(), CLIError> {
     let addr = Address::from_str(factory_addr)?;
 
-    let (payees, shares) = get_payout_data(db_deploy, &payout_csv, date).await.unwrap();
+    let (payees, shares) = get_payout_data(db_deploy, &payout_csv, date).await?;
+
+    if dry_run {
+        let plan = plan_new_payout(client.clone(), factory_addr, &payees, &shares).await?;
+        return plan.report(plan_file);
+    }
 
-    let total_sum = shares.clone().iter().fold(0_f64, |acc, x| acc + x);
+    let total_sum: Decimal = shares.iter().sum();
 
     info!("Total Sum from Payouts: {:?}", total_sum);
     info!("Total Payee Count: {:?}", payees.len());
 
+    let (parsed_payees, parsed_shares) = parse_payouts(&payees, &shares)?;
+    let mut journal = match resume {
+        Some(journal_path) => {
+            let journal = PayoutJournal::load(journal_path)?;
+            journal.verify(factory_addr, &parsed_payees, &parsed_shares)?;
+            info!("Resuming payout run from journal {:?}", journal_path);
+            journal
+        }
+        None => {
+            let date = chrono::offset::Utc::now().to_string();
+            let journal_path = PathBuf::from(&format!("./{}PayoutJournal.json", date));
+            info!("Writing payout journal to {:?}", journal_path);
+            PayoutJournal::create(
+                &journal_path,
+                factory_addr,
+                &parsed_payees,
+                &parsed_shares,
+                MAX_PAYEES_PER_PAYOUT,
+            )?
+        }
+    };
+
     let factory: PayoutFactory<S> = PayoutFactory::new(addr, client.clone());
 
-    let payout_size: i32 = payees.len() as i32;
-    let batches = if payout_size % (MAX_PAYEES_PER_PAYOUT as i32) == 0 {
-        payout_size / (MAX_PAYEES_PER_PAYOUT as i32)
-    } else {
-        payout_size / (MAX_PAYEES_PER_PAYOUT as i32) + 1
-    };
+    let journal_path = journal.path().to_path_buf();
+    let batches = journal.batches.len();
+    for i in 0..batches {
+        if journal.batches[i].status == BatchStatus::Submitted {
+            reconcile_submitted_batch(i, &mut journal, client.clone()).await?;
+        }
+    }
+
+    let required = journal
+        .batches
+        .iter()
+        .filter(|batch| batch.status != BatchStatus::Confirmed)
+        .flat_map(|batch| parsed_shares[batch.start_index..batch.end_index].iter())
+        .fold(U256::zero(), |acc, x| acc + x);
+    check_payout_preconditions(client.clone(), &factory, required).await?;
 
     info!("Deploying Payouts in {:?} batch deployments \n ", batches);
-    for i in 0..(batches as usize) {
-        let start_index = i * MAX_PAYEES_PER_PAYOUT;
+    for i in 0..batches {
+        if journal.batches[i].status == BatchStatus::Confirmed {
+            continue;
+        }
 
-        let payout_result = deploy_payout_batch(
-            start_index,
+        deploy_payout_batch(
+            i,
+            &mut journal,
             &payees,
             &shares,
             factory.clone(),
@@ -1221,17 +1768,45 @@ pub async fn new_payout<S: Middleware + 'static>(
             gas_price,
             retries,
         )
-        .await;
+        .await
+        .map_err(|error| {
+            error!("Resume the run with --resume {:?}", journal_path);
+            error
+        })?;
+    }
 
-        let _ = match payout_result {
-            Ok(payout) => payout,
-            Err(error) => panic!(
-                "Error deploying batch payout at start index range {:?}:  {:?}",
-                start_index, error
-            ),
-        };
+    Ok(())
+}
+
+/// Checks that `factory` can deploy payouts totalling `required` attoFIL: its
+/// balance must cover them, as `payout` sends the total value to each new
+/// splitter, and the sender of `client` must hold the admin role.
+pub async fn check_payout_preconditions<S: Middleware + 'static>(
+    client: Arc<S>,
+    factory: &PayoutFactory<S>,
+    required: U256,
+) -> Result<(), CLIError> {
+    let balance = client
+        .get_balance(factory.address(), None)
+        .await
+        .map_err(CLIError::rpc)?;
+    if balance < required {
+        return Err(CLIError::InsufficientFactoryBalance {
+            balance: format_u256(balance)?,
+            required: format_u256(required)?,
+        });
     }
 
+    let sender = client.default_sender().ok_or(CLIError::MissingSender)?;
+    if !factory.has_role(ADMIN_ROLE, sender).call().await? {
+        return Err(CLIError::MissingAdminRole(sender));
+    }
+
+    info!(
+        "Factory balance of {} FIL covers the {} FIL left to pay out",
+        format_fil(balance),
+        format_fil(required)
+    );
     Ok(())
 }
 
@@ -1239,47 +1814,54 @@ async fn get_payout_data(
     db_deploy: &bool,
     csv_path: &Option<PathBuf>,
     date: &str,
-) -> Result<(Vec<String>, Vec<f64>), Box<dyn Error>> {
+) -> Result<(Vec<String>, Vec<Decimal>), CLIError> {
     if *db_deploy {
-        let db_payout_records = get_payment_records(date).await.unwrap();
+        let db_payout_records = get_payment_records(date).await?;
         return Ok((db_payout_records.payees, db_payout_records.shares));
     } else {
         let (payees, shares) = match csv_path {
-            Some(csv_path) => parse_raw_payouts_from_csv(csv_path).await.unwrap(),
+            Some(csv_path) => {
+                validate_csv(csv_path)?;
+                parse_raw_payouts_from_csv(csv_path).await?
+            }
             None => {
-                panic!("Either payout-csv or db-deployment must be defined as CLI args");
+                return Err(CLIError::InvalidInput(String::from(
+                    "Either payout-csv or db-deployment must be defined as CLI args",
+                )));
             }
         };
         return Ok((payees, shares));
     }
 }
 
+/// EVM calldata of the `payout` call deploying a payout contract for
+/// `payees`, funded with the sum of `shares`.
+pub fn payout_calldata<S: Middleware + 'static>(
+    factory: &PayoutFactory<S>,
+    payees: Vec<FilAddress>,
+    shares: Vec<U256>,
+) -> Result<Vec<u8>, CLIError> {
+    let total_sum = shares.iter().fold(U256::from(0), |acc, x| acc + x);
+
+    Ok(factory
+        .payout(payees, shares, total_sum)
+        .calldata()
+        .ok_or_else(|| CLIError::Other(String::from("failed to encode calldata")))?
+        .to_vec())
+}
+
 pub fn propose_new_payout_callbytes<S: Middleware + 'static>(
     client: Arc<S>,
     factory_addr: &str,
     payees: Vec<FilAddress>,
     shares: Vec<U256>,
-) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+) -> Result<Vec<u8>, CLIError> {
     let addr = Address::from_str(factory_addr)?;
     let factory = PayoutFactory::new(addr, client.clone());
 
-    let total_sum = shares.clone().iter().fold(U256::from(0), |acc, x| acc + x);
-
-    let call_bytes = factory
-        .payout(payees, shares, total_sum)
-        .calldata()
-        .unwrap()
-        .to_vec();
+    let call_bytes = payout_calldata(&factory, payees, shares)?;
 
-    let num_bytes = call_bytes.len().to_be_bytes();
-    let num_bytes = num_bytes
-        .iter()
-        .filter(|x| **x != 0)
-        .map(|x| x.clone())
-        .collect::<Vec<u8>>();
-    let mut params = hex::decode(PARAMS_CBOR_HEADER[num_bytes.len() - 1])?;
-    params.extend(num_bytes);
-    params.extend(call_bytes.clone());
+    let params = encode_calldata_params(call_bytes)?;
 
     Ok(params)
 }
@@ -1287,25 +1869,36 @@ pub fn propose_new_payout_callbytes<S: Middleware + 'static>(
 async fn get_wallet(
     secret: PathBuf,
     provider: Provider<Http>,
-) -> Result<Arc<SignerMiddleware<Arc<Provider<Http>>, Wallet<SigningKey>>>, Box<dyn Error>> {
+) -> Result<Arc<SignerMiddleware<Arc<Provider<Http>>, Wallet<SigningKey>>>, CLIError> {
     let mnemonic = read_to_string(secret)?;
-    let client = get_wallet_signing_provider(provider, &mnemonic).await?;
+    let client = get_wallet_signing_provider(provider, &mnemonic)
+        .await
+        .map_err(CLIError::signing)?;
     let client = Arc::new(client);
     Ok(client)
 }
 
 pub async fn fund_factory_contract(
     factory_addr: &str,
-    amount: &i128,
+    amount: &Decimal,
     secret: Option<PathBuf>,
     provider: Provider<Http>,
     retries: usize,
     gas_price: U256,
-) {
-    let client = get_wallet(secret.unwrap(), provider).await.unwrap();
-    let addr = Address::from_str(factory_addr).unwrap();
+) -> Result<(), CLIError> {
+    let secret = secret.ok_or_else(|| {
+        CLIError::InvalidInput(String::from("funding the factory requires a --secret"))
+    })?;
+    let client = get_wallet(secret, provider).await?;
+    let addr = Address::from_str(factory_addr)?;
+    let amount = fil_to_atto(amount)?;
+    info!(
+        "funding {} with {} FIL ({} attoFIL)",
+        factory_addr,
+        format_fil(amount),
+        amount
+    );
     // craft the tx (Filecoin doesn't support legacy transactions)
-    let amount = U256::from(*amount);
     let mut fund_tx: TypedTransaction = Eip1559TransactionRequest::new()
         .to(addr)
         .value(amount)
@@ -1313,15 +1906,61 @@ pub async fn fund_factory_contract(
         .into(); // specify the `from` field so that the client knows which account to use
 
     let tx = fund_tx.clone();
-    set_tx_gas(
+    gas_policy().set_tx_gas(
         &mut fund_tx,
-        client.estimate_gas(&tx, None).await.unwrap(),
+        client
+            .estimate_gas(&tx, None)
+            .await
+            .map_err(|error| CLIError::middleware(error, CLIError::GasEstimation))?,
         gas_price,
-    );
+    )?;
 
     info!("estimated fund gas cost {:#?}", fund_tx.gas().unwrap());
 
-    send_tx(&fund_tx, client, retries).await.unwrap();
+    send_tx(&fund_tx, client, retries)
+        .await
+        .map_err(CLIError::rpc)?;
+    Ok(())
+}
+
+/// Funds a factory with a plain transfer of `amount` FIL to its f410 address,
+/// signed with any `SignatureMethod`. `factory_addr` is the Ethereum or
+/// Filecoin address of the factory.
+pub async fn fund_factory_contract_filecoin_signing(
+    provider: &Provider<Http>,
+    factory_addr: &str,
+    amount: &Decimal,
+    signing_method: &SignatureMethod,
+    signing_address: &str,
+    nonces: &mut NonceAllocator,
+) -> Result<String, CLIError> {
+    let to = match Address::from_str(factory_addr) {
+        Ok(eth_addr) => FilecoinAddress::new_delegated(EAM_ACTOR_ID, eth_addr.as_bytes())?,
+        Err(_) => FilecoinAddress::from_str(factory_addr)?,
+    };
+    let value = fil_to_token_amount(amount)?;
+    info!(
+        "funding {} with {} FIL ({} attoFIL)",
+        to,
+        value,
+        value.atto()
+    );
+
+    let mut message = Message {
+        version: 0,
+        to,
+        from: FilecoinAddress::from_str(signing_address)?,
+        // set when the message is pushed
+        sequence: 0,
+        value,
+        gas_limit: 0,
+        gas_fee_cap: TokenAmount::from_atto(BigInt::from_str("0")?),
+        gas_premium: TokenAmount::from_atto(BigInt::from_str("0")?),
+        method_num: 0, // plain value transfer
+        params: RawBytes::default(),
+    };
+
+    sign_and_push_message(provider, signing_method, nonces, &mut message).await
 }
 
 pub async fn deploy_factory_contract<S: Middleware + 'static>(
@@ -1329,21 +1968,26 @@ pub async fn deploy_factory_contract<S: Middleware + 'static>(
     retries: usize,
     gas_price: U256,
     address: Address,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), CLIError> {
     let mut contract = PayoutFactory::deploy(client.clone(), address)?;
     let tx = contract.deployer.tx.clone();
-    set_tx_gas(
+    gas_policy().set_tx_gas(
         &mut contract.deployer.tx,
-        client.estimate_gas(&tx, None).await?,
+        client
+            .estimate_gas(&tx, None)
+            .await
+            .map_err(|error| CLIError::middleware(error, CLIError::GasEstimation))?,
         gas_price,
-    );
+    )?;
 
     info!(
         "estimated deployment gas cost: {:#?}",
         contract.deployer.tx.gas().unwrap()
     );
 
-    let receipt = send_tx(&contract.deployer.tx, client, retries).await?;
+    let receipt = send_tx(&contract.deployer.tx, client, retries)
+        .await
+        .map_err(CLIError::rpc)?;
 
     let address = receipt
         .contract_address
@@ -1355,35 +1999,39 @@ pub async fn deploy_factory_contract<S: Middleware + 'static>(
     Ok(())
 }
 
-pub async fn generate_monthly_payout(date: &str, factory_address: &str) {
-    let formatted_date = format_date(date).unwrap();
+pub async fn generate_monthly_payout(date: &str, factory_address: &str) -> Result<(), CLIError> {
+    let formatted_date = format_date(date)?;
 
     let mut confirmation = String::new();
 
-    let month = Month::from_u32(formatted_date.month()).expect("Invalid MM format in date");
+    let month = Month::from_u32(formatted_date.month())
+        .ok_or_else(|| CLIError::InvalidInput(format!("Invalid MM format in date '{}'", date)))?;
     info!(
         "Type 'yes' to confirm you are generating payouts for {} {}",
         month.name(),
         formatted_date.year(),
     );
     let _ = io::stdout().flush();
-    let _ = std::io::stdin().read_line(&mut confirmation).unwrap();
+    std::io::stdin().read_line(&mut confirmation)?;
 
     if confirmation.trim().ne(&String::from("yes")) {
-        panic!("User rejected current date");
+        return Err(CLIError::UserAbort(String::from(
+            "User rejected current date",
+        )));
     }
 
-    let PayoutRecords { payees, shares } = get_payment_records(date).await.unwrap();
+    let PayoutRecords { payees, shares } = get_payment_records(date).await?;
 
     let csv_title = format!("Saturn-FVM-Payouts-{}.csv", date);
-    let path = PathBuf::from_str(csv_title.as_str()).unwrap();
-    write_payout_csv(&path, &payees, &shares).unwrap();
+    let path = PathBuf::from(csv_title);
+    write_payout_csv(&path, &payees, &shares)?;
 
     let csv_title = format!("Saturn-Contract-Fund-{}.csv", date);
-    let path = PathBuf::from_str(csv_title.as_str()).unwrap();
-    let payout_sum: f64 = shares.iter().sum();
-    write_payout_csv(&path, &vec![factory_address.to_string()], &vec![payout_sum]).unwrap();
+    let path = PathBuf::from(csv_title);
+    let payout_sum: Decimal = shares.iter().sum();
+    write_payout_csv(&path, &vec![factory_address.to_string()], &vec![payout_sum])?;
     info!("Sum from payouts {:#?}", payout_sum);
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -1405,16 +2053,14 @@ pub struct StateActorInfo {
     pub nonce: u64,
 }
 
-// Increase this value to increase the chances of transactions
-// completing successfully. For context, 130 is typical.
-const GAS_LIMIT_MULTIPLIER: u64 = 600;
-
-pub async fn get_gas_info(
+/// Gas of a message as estimated by the node, before the gas policy is
+/// applied.
+pub async fn estimate_message_gas(
     unsigned_message: Message,
     provider: Provider<Http>,
-    max_fee: &str,
-) -> TransactionGasInfo {
-    let max_fee = HashMap::from([("MaxFee", max_fee)]);
+) -> Result<TransactionGasInfo, CLIError> {
+    let max_fee = gas_policy().max_fee_spec();
+    let max_fee = HashMap::from([("MaxFee", max_fee.as_str())]);
 
     let gas_info: MessageTxAPI = provider
         .request::<(MessageTxAPI, HashMap<&str, &str>, ()), MessageTxAPI>(
@@ -1422,44 +2068,56 @@ pub async fn get_gas_info(
             (MessageTxAPI::Message(unsigned_message), max_fee, ()),
         )
         .await
-        .unwrap();
+        .map_err(|error| CLIError::middleware(error, CLIError::GasEstimation))?;
 
     let gas_info = gas_info.get_message();
-    TransactionGasInfo {
-        gas_limit: gas_info.gas_limit * (GAS_LIMIT_MULTIPLIER / 100),
+    Ok(TransactionGasInfo {
+        gas_limit: gas_info.gas_limit,
         gas_premium: gas_info.gas_premium,
         gas_fee_cap: gas_info.gas_fee_cap,
-    }
+    })
+}
+
+/// Estimates the gas of a message and applies the configured `GasPolicy`.
+pub async fn get_gas_info(
+    unsigned_message: Message,
+    provider: Provider<Http>,
+) -> Result<TransactionGasInfo, CLIError> {
+    let estimate = estimate_message_gas(unsigned_message, provider).await?;
+    gas_policy().apply_to_message(estimate)
 }
 
-pub async fn get_nonce(address: &str, provider: Provider<Http>) -> u64 {
+pub async fn get_nonce(address: &str, provider: Provider<Http>) -> Result<u64, CLIError> {
     let result: StateActorInfo = provider
         .request::<(&str, ()), StateActorInfo>("Filecoin.StateGetActor", (address, ()))
-        .await
-        .unwrap();
+        .await?;
 
-    result.nonce
+    Ok(result.nonce)
 }
 
-pub async fn get_filecoin_ledger(ledger_account: u32) -> FilecoinApp<TransportNativeHID> {
-    let hid_api: Lazy<HidApi> = Lazy::new(|| HidApi::new().expect("Failed to create Hidapi"));
+pub async fn get_filecoin_ledger(
+    ledger_account: u32,
+) -> Result<FilecoinApp<TransportNativeHID>, CLIError> {
+    let hid_api = HidApi::new().map_err(CLIError::ledger)?;
 
-    let app =
-        FilecoinApp::new(TransportNativeHID::new(&hid_api).expect("unable to create transport"));
+    let app = FilecoinApp::new(TransportNativeHID::new(&hid_api).map_err(CLIError::ledger)?);
 
     let mut bip_path = BIP44_PATH;
 
     bip_path.account = ledger_account | 0x8000_0000;
 
-    let addr = app.address(&bip_path, false).await.unwrap();
+    let addr = app
+        .address(&bip_path, false)
+        .await
+        .map_err(CLIError::ledger)?;
     info!(
         "Connected to Filecoin Ledger on address: {:#?}",
         addr.addr_string
     );
-    app
+    Ok(app)
 }
 
-pub fn random_filecoin_address(testnet: bool) -> Result<String, Box<dyn Error>> {
+pub fn random_filecoin_address(testnet: bool) -> Result<String, CLIError> {
     let mut rng = ethers::prelude::rand::thread_rng();
     let mut bytes = [0u8; SECP_PUB_LEN];
     ethers::prelude::rand::Rng::fill(&mut rng, &mut bytes[..]);
@@ -1470,7 +2128,9 @@ pub fn random_filecoin_address(testnet: bool) -> Result<String, Box<dyn Error>>
     Ok(addr.to_string())
 }
 
-/// Formats an ethers U256 type to an f64 to a precision of 5 significant digits.
+/// Formats an ethers U256 attoFIL value to an exact FIL decimal.
+///
+/// Fails if the value has more digits than a `Decimal` holds.
 ///
 /// Example:
 ///
@@ -1479,20 +2139,49 @@ pub fn random_filecoin_address(testnet: bool) -> Result<String, Box<dyn Error>>
 /// use cli::utils::format_u256;
 /// let ethers_value = U256::from(35000000000000000_u128);
 /// # println!("U256 Value: {:?}", ethers_value);
-/// let converted_f64_value = format_u256(ethers_value);
-/// # println!("Converted f64: {:?}", converted_f64_value);
+/// let converted_value = format_u256(ethers_value).unwrap();
+/// assert_eq!(converted_value.to_string(), "0.035");
+/// ```
+pub fn format_u256(value: U256) -> Result<Decimal, CLIError> {
+    Decimal::from_str_exact(&format_fil(value)).map_err(|error| {
+        CLIError::Other(format!(
+            "{} attoFIL does not fit in a FIL decimal: {}",
+            value, error
+        ))
+    })
+}
+
+/// Formats an ethers U256 attoFIL value as FIL, from its decimal digits such
+/// that any value can be displayed.
+///
+/// Example:
+///
+/// ```
+/// use ethers::types::{U256};
+/// use cli::utils::format_fil;
+/// assert_eq!(format_fil(U256::from(35000000000000000_u128)), "0.035");
+/// assert_eq!(format_fil(U256::MAX).len(), 79);
 /// ```
-pub fn format_u256(value: U256) -> f64 {
-    let precision_factor = 10_f64.powf(5.0);
-    let divider = &*ATTO_FIL / precision_factor;
-    let value = value / U256::from(divider.to_u128().unwrap());
-    value.as_u64().to_f64().unwrap() / precision_factor
+pub fn format_fil(value: U256) -> String {
+    let decimals = FIL_DECIMALS as usize;
+    let digits = format!("{:0>width$}", value.to_string(), width = decimals + 1);
+    let (fil, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        fil.to_string()
+    } else {
+        format!("{}.{}", fil, fraction)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{format_u256, hex_to_ascii, ATTO_FIL};
+    use super::{
+        encode_calldata_params, fil_to_atto, format_fil, format_u256, hex_to_ascii,
+        parse_fil_amount, release_select_chunks, ATTO_FIL,
+    };
     use ethabi::ethereum_types::U256;
+    use rust_decimal::Decimal;
 
     #[test]
     fn test_random_filecoin_address() {
@@ -1520,24 +2209,93 @@ mod tests {
     #[test]
     fn test_format_u256() {
         // Generate potential earnings ranging from 0.0001 to 10,000 FIL.
-        let test_values: Vec<f64> = (-4..5).map(|exp| 10_f64.powf(exp as f64)).collect();
+        let test_values: Vec<Decimal> = (-4..5)
+            .map(|exp: i32| {
+                if exp < 0 {
+                    Decimal::new(1, exp.unsigned_abs())
+                } else {
+                    Decimal::from(10_u64.pow(exp as u32))
+                }
+            })
+            .collect();
 
         // Convert those earnings to U256 values as they will be returned from the contract.
         let u256_test_values: Vec<U256> = test_values
             .iter()
-            .map(|value| U256::from((value * &*ATTO_FIL) as u128))
+            .map(|value| fil_to_atto(value).unwrap())
             .collect();
+        assert_eq!(u256_test_values[4], U256::from(ATTO_FIL));
 
-        // Convert back the values to f64
-        let formatted_u256_values: Vec<f64> = u256_test_values
+        // Convert back the values to decimals
+        let formatted_u256_values: Vec<Decimal> = u256_test_values
             .iter()
-            .map(|value| format_u256(*value))
+            .map(|value| format_u256(*value).unwrap())
             .collect();
 
         println!("Formatted Values: {:?}", formatted_u256_values);
         assert_eq!(test_values, formatted_u256_values);
     }
 
+    #[test]
+    fn test_format_large_u256() {
+        // above u128 and above the 96 bit mantissa of a Decimal
+        let large = U256::from(u128::MAX) * 1000 + 5;
+        assert!(format_u256(large).is_err());
+        assert!(format_u256(U256::MAX).is_err());
+        assert_eq!(
+            format_fil(large),
+            "340282366920938463463374.607431768211455005"
+        );
+        assert_eq!(format_fil(U256::zero()), "0");
+        assert_eq!(format_fil(U256::from(ATTO_FIL) * 3), "3");
+        assert_eq!(format_fil(U256::from(1)), "0.000000000000000001");
+    }
+
+    #[test]
+    fn test_exact_fil_amounts() {
+        // 0.1 + 0.2 is not exact in floating point
+        let amounts = ["0.1", "0.2", "123456789.123456789123456789"];
+        let atto: Vec<U256> = amounts
+            .iter()
+            .map(|amount| fil_to_atto(&parse_fil_amount(amount).unwrap()).unwrap())
+            .collect();
+
+        assert_eq!(atto[0] + atto[1], U256::from(300_000_000_000_000_000_u128));
+        assert_eq!(
+            atto[2],
+            U256::from_dec_str("123456789123456789123456789").unwrap()
+        );
+        assert_eq!(
+            format_u256(atto[2]).unwrap(),
+            parse_fil_amount(amounts[2]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_fil_amounts() {
+        assert!(parse_fil_amount("1.0000000000000000001").is_err());
+        assert!(parse_fil_amount("one").is_err());
+        assert!(parse_fil_amount("").is_err());
+        // trailing zeros do not count towards the precision
+        assert!(parse_fil_amount("1.0000000000000000000000").is_ok());
+        assert!(fil_to_atto(&parse_fil_amount("-1").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_encode_calldata_params() {
+        let params = encode_calldata_params(vec![7; 0x20]).unwrap();
+        assert_eq!(params[..2], [0x58, 0x20]);
+        assert_eq!(params.len(), 2 + 0x20);
+
+        // zero bytes in the length must be kept
+        let params = encode_calldata_params(vec![7; 0x9c00]).unwrap();
+        assert_eq!(params[..3], [0x59, 0x9c, 0x00]);
+        assert_eq!(params.len(), 3 + 0x9c00);
+
+        let params = encode_calldata_params(vec![7; 0x10000]).unwrap();
+        assert_eq!(params[..5], [0x5a, 0x00, 0x01, 0x00, 0x00]);
+    }
+
     #[test]
     fn test_valid_hex_to_private_key_conversion() {
         let hex = "7b22507269766174654b6579223a2252616e646f6d4b657956616c7565227d";
@@ -1568,6 +2326,19 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_release_select_chunks() {
+        let releasable: Vec<(U256, U256)> = (0..26)
+            .map(|index| (U256::from(index), U256::from(ATTO_FIL)))
+            .collect();
+        let chunks = release_select_chunks(&releasable);
+        let sizes: Vec<usize> = chunks.iter().map(|(indices, _)| indices.len()).collect();
+        assert_eq!(sizes, vec![12, 12, 2]);
+        assert_eq!(chunks[1].0[0], U256::from(12));
+        assert_eq!(chunks[2].1, U256::from(2 * ATTO_FIL));
+        assert!(release_select_chunks(&[]).is_empty());
+    }
+
     #[test]
     fn test_empty_hex() {
         let hex = "";
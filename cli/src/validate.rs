@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use fevm_utils::check_address_string;
+use log::{error, info};
+use rust_decimal::Decimal;
+
+use crate::error::CLIError;
+use crate::utils::parse_fil_amount;
+
+/// Columns expected in a payout CSV, in order.
+const PAYOUT_CSV_HEADER: [&str; 2] = ["Recipient", "FIL"];
+
+/// A problem found on a line of a payout CSV.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvIssue {
+    /// 1-based line number, the header being line 1.
+    pub line: u64,
+    pub message: String,
+}
+
+impl std::fmt::Display for CsvIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Returns the network prefix (`f` or `t`) of a Filecoin address string.
+fn network_prefix(address: &str) -> Option<char> {
+    address
+        .chars()
+        .next()
+        .filter(|prefix| *prefix == 'f' || *prefix == 't')
+}
+
+/// Checks every row of a payout CSV and returns all the issues found.
+///
+/// Unlike `parse_raw_payouts_from_csv`, which stops at the first bad row, this
+/// reports every problem so that a CSV can be fixed in one go before it is
+/// deployed.
+pub fn validate_payout_csv(payout_csv: &PathBuf) -> Result<Vec<CsvIssue>, CLIError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_path(payout_csv)?;
+    let mut issues = Vec::new();
+
+    let header = reader.headers()?.clone();
+    if header.iter().collect::<Vec<&str>>() != PAYOUT_CSV_HEADER {
+        issues.push(CsvIssue {
+            line: 1,
+            message: format!(
+                "expected header '{}', found '{}'",
+                PAYOUT_CSV_HEADER.join(","),
+                header.iter().collect::<Vec<&str>>().join(",")
+            ),
+        });
+    }
+
+    let mut seen: HashMap<Vec<u8>, u64> = HashMap::new();
+    let mut network: Option<(char, u64)> = None;
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                issues.push(CsvIssue {
+                    line: err.position().map_or(0, |position| position.line()),
+                    message: format!("malformed row: {}", err),
+                });
+                continue;
+            }
+        };
+        let line = record.position().map_or(0, |position| position.line());
+        let mut issue = |message: String| issues.push(CsvIssue { line, message });
+
+        if record.len() != PAYOUT_CSV_HEADER.len() {
+            issue(format!(
+                "expected {} columns, found {}",
+                PAYOUT_CSV_HEADER.len(),
+                record.len()
+            ));
+            continue;
+        }
+
+        let recipient = record[0].trim();
+        match check_address_string(recipient) {
+            Ok(address) => {
+                if let Some(first_line) = seen.get(&address.bytes) {
+                    issue(format!(
+                        "duplicate recipient {}, first listed on line {}",
+                        recipient, first_line
+                    ));
+                } else {
+                    seen.insert(address.bytes, line);
+                }
+                match (network_prefix(recipient), network) {
+                    (Some(prefix), None) => network = Some((prefix, line)),
+                    (Some(prefix), Some((expected, first_line))) if prefix != expected => {
+                        issue(format!(
+                            "recipient {} is not on the '{}' network of line {}",
+                            recipient, expected, first_line
+                        ))
+                    }
+                    _ => {}
+                }
+            }
+            Err(err) => issue(format!("invalid recipient '{}': {}", recipient, err)),
+        }
+
+        match parse_fil_amount(&record[1]) {
+            Ok(amount) if amount <= Decimal::ZERO => {
+                issue(format!("amount must be positive, found {}", amount))
+            }
+            Ok(_) => {}
+            Err(err) => issue(err.to_string()),
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Validates a payout CSV, logging every issue with its line number.
+///
+/// Fails with `CLIError::InvalidPayoutCsv` if any issue is found.
+pub fn validate_csv(payout_csv: &PathBuf) -> Result<(), CLIError> {
+    let issues = validate_payout_csv(payout_csv)?;
+    if issues.is_empty() {
+        info!("{:?} is a valid payout CSV", payout_csv);
+        return Ok(());
+    }
+    for issue in issues.iter() {
+        error!("{}", issue);
+    }
+    Err(CLIError::InvalidPayoutCsv(issues.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_payout_csv;
+    use assert_fs::prelude::*;
+
+    #[test]
+    fn test_validate_payout_csv() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let csv = dir.child("payouts.csv");
+        csv.write_str(
+            "Recipient,FIL\n\
+             f01,1.5\n\
+             f02,0\n\
+             f01,2\n\
+             not-an-address,1\n\
+             t03,1\n\
+             f04,-1\n\
+             f05,1,extra\n\
+             f06,0.0000000000000000001\n",
+        )
+        .unwrap();
+
+        let issues = validate_payout_csv(&csv.path().to_path_buf()).unwrap();
+        let lines: Vec<u64> = issues.iter().map(|issue| issue.line).collect();
+        assert_eq!(lines, vec![3, 4, 5, 6, 7, 8, 9]);
+        assert!(issues[1].message.contains("line 2"));
+    }
+
+    #[test]
+    fn test_validate_payout_csv_header() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let csv = dir.child("payouts.csv");
+        csv.write_str("Recipient,FIL,Note\nf01,1,a\n").unwrap();
+
+        let issues = validate_payout_csv(&csv.path().to_path_buf()).unwrap();
+        let lines: Vec<u64> = issues.iter().map(|issue| issue.line).collect();
+        assert_eq!(lines, vec![1, 2]);
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use contract_bindings::payment_splitter_native_addr::PaymentSplitterNativeAddr as PaymentSplitter;
+use contract_bindings::payout_factory_native_addr::PayoutFactoryNativeAddr as PayoutFactory;
+use ethers::abi::Address;
+use ethers::providers::{Http, Provider};
+use ethers::types::U256;
+use fevm_utils::check_address_string;
+use fvm_shared::address::Address as FilecoinAddress;
+use log::info;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::error::CLIError;
+use crate::utils::{
+    fil_to_atto, format_fil, format_table, format_u256, parse_raw_payouts_from_csv,
+};
+
+/// Reconciliation outcome for a single payee.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PayeeStatus {
+    /// Deployed exactly once with the expected amount.
+    Deployed,
+    /// Not found in any of the inspected splitters.
+    Missing,
+    /// Found in more than one splitter.
+    Duplicated,
+    /// Deployed once but with a different amount.
+    AmountMismatch,
+    /// Found on chain but not in the payout CSV.
+    Unexpected,
+}
+
+impl std::fmt::Display for PayeeStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Tabled)]
+#[serde(rename_all = "PascalCase")]
+pub struct PayeeReconciliation {
+    pub recipient: String,
+    #[tabled(display_with = "display_amount")]
+    pub expected: Option<Decimal>,
+    #[tabled(display_with = "display_amount")]
+    pub deployed: Option<Decimal>,
+    /// Indices of the splitters the payee appears in.
+    #[tabled(display_with = "display_indices")]
+    #[serde(serialize_with = "serialize_indices")]
+    pub splitters: Vec<usize>,
+    pub status: PayeeStatus,
+}
+
+/// Payees and shares of a deployed `PaymentSplitterNativeAddr`.
+#[derive(Debug, Clone)]
+pub struct SplitterPayees {
+    pub index: usize,
+    pub address: Address,
+    /// Raw Filecoin address bytes and shares in attoFIL.
+    pub payees: Vec<(Vec<u8>, U256)>,
+    pub total_shares: U256,
+}
+
+fn display_amount(amount: &Option<Decimal>) -> String {
+    match amount {
+        Some(amount) => amount.to_string(),
+        None => String::from("-"),
+    }
+}
+
+fn display_indices(indices: &Vec<usize>) -> String {
+    indices
+        .iter()
+        .map(|index| index.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn serialize_indices<S: serde::Serializer>(
+    indices: &Vec<usize>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&display_indices(indices))
+}
+
+/// Compares the expected payouts against the payees of deployed splitters.
+///
+/// `expected` holds the recipient as written in the CSV, its address bytes and
+/// the amount in attoFIL.
+pub fn reconcile_payouts(
+    expected: &[(String, Vec<u8>, U256)],
+    splitters: &[SplitterPayees],
+) -> Result<Vec<PayeeReconciliation>, CLIError> {
+    let mut deployed: HashMap<&[u8], Vec<(usize, U256)>> = HashMap::new();
+    for splitter in splitters.iter() {
+        for (payee, share) in splitter.payees.iter() {
+            deployed
+                .entry(payee.as_slice())
+                .or_default()
+                .push((splitter.index, *share));
+        }
+    }
+
+    let mut rows: Vec<PayeeReconciliation> = expected
+        .iter()
+        .map(|(recipient, bytes, amount)| {
+            let found = deployed.remove(bytes.as_slice()).unwrap_or_default();
+            let status = match found.as_slice() {
+                [] => PayeeStatus::Missing,
+                [(_, share)] if share == amount => PayeeStatus::Deployed,
+                [_] => PayeeStatus::AmountMismatch,
+                _ => PayeeStatus::Duplicated,
+            };
+            Ok(PayeeReconciliation {
+                recipient: recipient.clone(),
+                expected: Some(format_u256(*amount)?),
+                deployed: if found.is_empty() {
+                    None
+                } else {
+                    Some(format_u256(
+                        found
+                            .iter()
+                            .fold(U256::zero(), |acc, (_, share)| acc + share),
+                    )?)
+                },
+                splitters: found.iter().map(|(index, _)| *index).collect(),
+                status,
+            })
+        })
+        .collect::<Result<_, CLIError>>()?;
+
+    let mut unexpected: Vec<PayeeReconciliation> = deployed
+        .into_iter()
+        .map(|(payee, found)| {
+            Ok(PayeeReconciliation {
+                recipient: match FilecoinAddress::from_bytes(payee) {
+                    Ok(addr) => addr.to_string(),
+                    Err(_) => format!("0x{}", hex::encode(payee)),
+                },
+                expected: None,
+                deployed: Some(format_u256(
+                    found
+                        .iter()
+                        .fold(U256::zero(), |acc, (_, share)| acc + share),
+                )?),
+                splitters: found.iter().map(|(index, _)| *index).collect(),
+                status: PayeeStatus::Unexpected,
+            })
+        })
+        .collect::<Result<_, CLIError>>()?;
+    unexpected.sort_by(|a, b| a.splitters.cmp(&b.splitters));
+    rows.extend(unexpected);
+    Ok(rows)
+}
+
+/// Reads the payees, shares and total shares of every splitter created by the
+/// factory from `start_index` onwards.
+pub async fn get_splitter_payees(
+    provider: &Provider<Http>,
+    factory_address: &str,
+    start_index: usize,
+) -> Result<Vec<SplitterPayees>, CLIError> {
+    let client = Arc::new(provider.clone());
+    let factory = PayoutFactory::new(Address::from_str(factory_address)?, client.clone());
+    let payouts = factory.payouts().call().await?;
+
+    let mut splitters = Vec::new();
+    for (index, address) in payouts.into_iter().enumerate().skip(start_index) {
+        let splitter = PaymentSplitter::new(address, client.clone());
+        let total_shares = splitter.total_shares().call().await?;
+        let mut payees = Vec::new();
+        for payee in splitter.payees().call().await? {
+            let share = splitter.shares(payee.clone()).call().await?;
+            payees.push((payee.data.to_vec(), share));
+        }
+        let sum = payees
+            .iter()
+            .fold(U256::zero(), |acc, (_, share)| acc + share);
+        if sum != total_shares {
+            return Err(CLIError::Other(format!(
+                "splitter {} ({:?}) reports {} total shares but its payees sum to {}",
+                index, address, total_shares, sum
+            )));
+        }
+        info!(
+            "Splitter {} ({:?}): {} payees, {} FIL",
+            index,
+            address,
+            payees.len(),
+            format_fil(total_shares)
+        );
+        splitters.push(SplitterPayees {
+            index,
+            address,
+            payees,
+            total_shares,
+        });
+    }
+    Ok(splitters)
+}
+
+/// Reconciles a payout CSV against the splitters deployed by a factory.
+///
+/// Only splitters from `start_index` onwards are inspected, such that payees
+/// of earlier payout periods are not reported as duplicates.
+pub async fn verify_payout(
+    provider: &Provider<Http>,
+    factory_address: &str,
+    payout_csv: &PathBuf,
+    start_index: usize,
+    report: &Option<PathBuf>,
+) -> Result<Vec<PayeeReconciliation>, CLIError> {
+    let (payees, shares) = parse_raw_payouts_from_csv(payout_csv).await?;
+    let mut expected = Vec::new();
+    for (payee, share) in payees.into_iter().zip(shares.iter()) {
+        let bytes = check_address_string(&payee)
+            .map_err(CLIError::invalid_input)?
+            .bytes;
+        expected.push((payee, bytes, fil_to_atto(share)?));
+    }
+
+    let splitters = get_splitter_payees(provider, factory_address, start_index).await?;
+    let rows = reconcile_payouts(&expected, &splitters)?;
+
+    if let Some(report) = report {
+        let mut writer = csv::Writer::from_path(report)?;
+        for row in rows.iter() {
+            writer.serialize(row)?;
+        }
+        writer.flush()?;
+        info!("Wrote reconciliation report to {:?}", report);
+    }
+
+    let count = |status: PayeeStatus| rows.iter().filter(|row| row.status == status).count();
+    let discrepancies: Vec<&PayeeReconciliation> = rows
+        .iter()
+        .filter(|row| row.status != PayeeStatus::Deployed)
+        .collect();
+
+    let mut summary = format!(
+        "\n\n  Payout verification against {} splitters \n\n  Deployed: {}\n  Missing: {}\n  Duplicated: {}\n  Amount mismatch: {}\n  Unexpected: {}\n",
+        splitters.len(),
+        count(PayeeStatus::Deployed),
+        count(PayeeStatus::Missing),
+        count(PayeeStatus::Duplicated),
+        count(PayeeStatus::AmountMismatch),
+        count(PayeeStatus::Unexpected),
+    );
+    if !discrepancies.is_empty() {
+        summary = format!("{}\n{}", summary, format_table(discrepancies));
+    }
+    info!("{}", summary);
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{reconcile_payouts, PayeeStatus, SplitterPayees};
+    use ethers::abi::Address;
+    use ethers::types::U256;
+
+    fn splitter(index: usize, payees: Vec<(u8, u64)>) -> SplitterPayees {
+        let payees: Vec<(Vec<u8>, U256)> = payees
+            .into_iter()
+            .map(|(id, share)| (vec![0, id], U256::from(share)))
+            .collect();
+        SplitterPayees {
+            index,
+            address: Address::zero(),
+            total_shares: payees.iter().fold(U256::zero(), |acc, (_, s)| acc + s),
+            payees,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_payouts() {
+        let expected: Vec<(String, Vec<u8>, U256)> = (1..=5)
+            .map(|id: u8| (format!("f0{}", id), vec![0, id], U256::from(10)))
+            .collect();
+        let splitters = vec![
+            splitter(3, vec![(1, 10), (2, 10), (3, 9)]),
+            splitter(4, vec![(2, 10), (6, 10)]),
+        ];
+
+        let rows = reconcile_payouts(&expected, &splitters).unwrap();
+        let statuses: Vec<PayeeStatus> = rows.iter().map(|row| row.status).collect();
+
+        assert_eq!(
+            statuses,
+            vec![
+                PayeeStatus::Deployed,
+                PayeeStatus::Duplicated,
+                PayeeStatus::AmountMismatch,
+                PayeeStatus::Missing,
+                PayeeStatus::Missing,
+                PayeeStatus::Unexpected,
+            ]
+        );
+        assert_eq!(rows[2].recipient, "f03");
+        assert_eq!(rows[1].splitters, vec![3, 4]);
+    }
+}
@@ -1,7 +1,7 @@
 use assert_cmd::prelude::*;
 use assert_fs::fixture::FileWriteStr;
 use assert_fs::NamedTempFile;
-use cli::utils::{random_filecoin_address, ATTO_FIL};
+use cli::utils::random_filecoin_address;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::env;
@@ -79,15 +79,15 @@ fn cli_2_fund() -> Result<(), Box<dyn std::error::Error>> {
     let mut args = get_const_cli_args();
 
     let factory_addr = &FACTORY_ADDRESS.lock().unwrap().to_string();
-    let amount = (0.01 * &*ATTO_FIL) as u128;
-    let amount = amount.to_string();
+    // in FIL
+    let amount = "0.01";
 
     let mut new_payout_args = vec![
         "fund",
         "--factory-addr",
         factory_addr.as_str(),
         "--amount",
-        amount.as_str(),
+        amount,
     ];
     args.append(&mut new_payout_args);
 